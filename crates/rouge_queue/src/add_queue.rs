@@ -0,0 +1,56 @@
+use std::hash::Hash;
+
+use bevy_ecs::prelude::*;
+
+use crate::{BoundedQueue, BroadcastQueue, CoalescingQueue, OverflowPolicy, PriorityQueue, Queue};
+
+/// Trait for types that can add a [`Queue`] resource.
+pub trait AddQueue {
+    /// Add a [`Queue`] of type `T` to the `World`.
+    fn add_queue<T: Send + 'static>(&mut self);
+
+    /// Add a [`BoundedQueue`] of type `T` to the `World`, holding at most
+    /// `capacity` items. Does nothing if the queue already exists.
+    fn add_bounded_queue<T: Send + 'static>(&mut self, capacity: usize, policy: OverflowPolicy);
+
+    /// Add a [`CoalescingQueue`] of items of type `T` keyed by `K` to the
+    /// `World`.
+    fn add_coalescing_queue<K, T>(&mut self)
+    where
+        K: Eq + Hash + Clone + Send + 'static,
+        T: Send + 'static;
+
+    /// Add a [`PriorityQueue`] of type `T` to the `World`.
+    fn add_priority_queue<T: Send + 'static>(&mut self);
+
+    /// Add a [`BroadcastQueue`] of type `T` to the `World`.
+    fn add_broadcast_queue<T: Send + 'static>(&mut self);
+}
+
+impl AddQueue for World {
+    fn add_queue<T: Send + 'static>(&mut self) {
+        self.init_resource::<Queue<T>>();
+    }
+
+    fn add_bounded_queue<T: Send + 'static>(&mut self, capacity: usize, policy: OverflowPolicy) {
+        if !self.contains_resource::<BoundedQueue<T>>() {
+            self.insert_resource(BoundedQueue::<T>::new(capacity, policy));
+        }
+    }
+
+    fn add_coalescing_queue<K, T>(&mut self)
+    where
+        K: Eq + Hash + Clone + Send + 'static,
+        T: Send + 'static,
+    {
+        self.init_resource::<CoalescingQueue<K, T>>();
+    }
+
+    fn add_priority_queue<T: Send + 'static>(&mut self) {
+        self.init_resource::<PriorityQueue<T>>();
+    }
+
+    fn add_broadcast_queue<T: Send + 'static>(&mut self) {
+        self.init_resource::<BroadcastQueue<T>>();
+    }
+}
@@ -0,0 +1,251 @@
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use bevy_ecs::{prelude::*, system::SystemParam};
+use crossbeam::queue::ArrayQueue;
+
+/// What a [`BoundedQueue`] does with an item pushed while it is full.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Drop the item being pushed.
+    #[default]
+    DropNewest,
+    /// Drop the oldest item in the queue to make room for the new one.
+    DropOldest,
+    /// Return the item being pushed to the caller as a [`QueueFull`] error.
+    Reject,
+}
+
+/// Error returned when pushing to a full [`BoundedQueue`] with the
+/// [`OverflowPolicy::Reject`] policy. Contains the rejected item.
+#[derive(Debug, PartialEq, Eq)]
+pub struct QueueFull<T>(pub T);
+
+impl<T> fmt::Display for QueueFull<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "queue `{}` is full", std::any::type_name::<T>())
+    }
+}
+
+impl<T: fmt::Debug> std::error::Error for QueueFull<T> {}
+
+/// A multi-producer multi-consumer queue with a fixed capacity.
+///
+/// Unlike [`Queue`](crate::Queue), memory use can't grow without limit when
+/// items are pushed faster than they are popped. What happens to items pushed
+/// while the queue is full is decided by its [`OverflowPolicy`].
+#[derive(Resource)]
+pub struct BoundedQueue<T> {
+    q: ArrayQueue<T>,
+    policy: OverflowPolicy,
+    dropped: AtomicUsize,
+}
+
+impl<T> BoundedQueue<T> {
+    /// Create a new queue that holds at most `capacity` items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize, policy: OverflowPolicy) -> Self {
+        Self {
+            q: ArrayQueue::new(capacity),
+            policy,
+            dropped: AtomicUsize::new(0),
+        }
+    }
+
+    /// The maximum number of items the queue can hold.
+    pub fn capacity(&self) -> usize {
+        self.q.capacity()
+    }
+
+    /// The policy applied when pushing to a full queue.
+    pub fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    /// The number of items in the queue.
+    pub fn len(&self) -> usize {
+        self.q.len()
+    }
+
+    /// Test if the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.q.is_empty()
+    }
+
+    /// Test if the queue is full.
+    pub fn is_full(&self) -> bool {
+        self.q.is_full()
+    }
+
+    /// The total number of items dropped because the queue was full. Items
+    /// rejected with [`OverflowPolicy::Reject`] are returned to the caller and
+    /// not counted.
+    pub fn dropped(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Push an item onto the queue, applying the queue's [`OverflowPolicy`] if
+    /// it is full. Only returns an error with the [`OverflowPolicy::Reject`]
+    /// policy.
+    pub fn push(&self, value: T) -> Result<(), QueueFull<T>> {
+        match self.policy {
+            OverflowPolicy::DropNewest => {
+                if self.q.push(value).is_err() {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+                Ok(())
+            }
+            OverflowPolicy::DropOldest => {
+                if self.q.force_push(value).is_some() {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+                Ok(())
+            }
+            OverflowPolicy::Reject => self.q.push(value).map_err(QueueFull),
+        }
+    }
+
+    /// Pop an item off of the queue. Returns `None` if the queue is empty.
+    pub fn pop(&self) -> Option<T> {
+        self.q.pop()
+    }
+
+    /// Iterate over items in the queue. This drains the queue, but does not
+    /// consume the `BoundedQueue` itself.
+    pub fn iter(&self) -> BoundedQueueIter<'_, T> {
+        BoundedQueueIter { q: self }
+    }
+}
+
+/// An iterator for `BoundedQueue`.
+///
+/// Created by calling [`BoundedQueue::iter`]. See its documentation for more.
+pub struct BoundedQueueIter<'a, T> {
+    q: &'a BoundedQueue<T>,
+}
+
+impl<'a, T> Iterator for BoundedQueueIter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.q.pop()
+    }
+}
+
+/// Push items onto a `BoundedQueue`.
+///
+/// Used as a convenience `SystemParam` rather than operating on the underlying
+/// [`BoundedQueue`] directly.
+#[derive(SystemParam)]
+pub struct BoundedQueueWriter<'w, 's, E>
+where
+    E: Send + 'static,
+{
+    queue: Res<'w, BoundedQueue<E>>,
+    #[system_param(ignore)]
+    marker: PhantomData<&'s ()>,
+}
+
+impl<'w, 's, E> BoundedQueueWriter<'w, 's, E>
+where
+    E: Send + 'static,
+{
+    /// Push an item onto the `BoundedQueue`, applying its [`OverflowPolicy`]
+    /// if it is full.
+    pub fn push(&self, value: E) -> Result<(), QueueFull<E>> {
+        self.queue.push(value)
+    }
+}
+
+/// Pop items from a `BoundedQueue`.
+///
+/// Used as a convenience `SystemParam` rather than operating on the underlying
+/// [`BoundedQueue`] directly.
+#[derive(SystemParam)]
+pub struct BoundedQueueReader<'w, 's, E>
+where
+    E: Send + 'static,
+{
+    queue: Res<'w, BoundedQueue<E>>,
+    #[system_param(ignore)]
+    marker: PhantomData<&'s ()>,
+}
+
+impl<'w, 's, E> BoundedQueueReader<'w, 's, E>
+where
+    E: Send + 'static,
+{
+    /// Check if the queue is empty without modifying the contents of the
+    /// `BoundedQueue`.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// The total number of items dropped because the queue was full.
+    pub fn dropped(&self) -> usize {
+        self.queue.dropped()
+    }
+
+    /// Pop the top item off of the queue.
+    pub fn pop(&self) -> Option<E> {
+        self.queue.pop()
+    }
+
+    /// Iterate over through items in the `BoundedQueue`. This drains the
+    /// `queue`, but does not consume the underlying `BoundedQueue`.
+    pub fn iter(&self) -> BoundedQueueIter<'_, E> {
+        self.queue.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(policy: OverflowPolicy) -> (BoundedQueue<u32>, Vec<Result<(), QueueFull<u32>>>) {
+        let queue = BoundedQueue::new(3, policy);
+        let results = (1..=5).map(|i| queue.push(i)).collect();
+        (queue, results)
+    }
+
+    #[test]
+    fn drop_newest() {
+        let (queue, results) = fill(OverflowPolicy::DropNewest);
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(queue.dropped(), 2);
+        assert_eq!(queue.iter().collect::<Vec<_>>(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn drop_oldest() {
+        let (queue, results) = fill(OverflowPolicy::DropOldest);
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(queue.dropped(), 2);
+        assert_eq!(queue.iter().collect::<Vec<_>>(), [3, 4, 5]);
+    }
+
+    #[test]
+    fn reject() {
+        let (queue, results) = fill(OverflowPolicy::Reject);
+        assert_eq!(
+            results,
+            [Ok(()), Ok(()), Ok(()), Err(QueueFull(4)), Err(QueueFull(5))]
+        );
+        assert_eq!(queue.dropped(), 0);
+        assert_eq!(queue.iter().collect::<Vec<_>>(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn accepts_items_again_once_drained() {
+        let (queue, _) = fill(OverflowPolicy::Reject);
+        assert!(queue.is_full());
+        assert_eq!(queue.pop(), Some(1));
+        assert_eq!(queue.push(6), Ok(()));
+        assert_eq!(queue.iter().collect::<Vec<_>>(), [2, 3, 6]);
+        assert!(queue.is_empty());
+    }
+}
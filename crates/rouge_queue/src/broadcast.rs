@@ -0,0 +1,310 @@
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use bevy_ecs::{prelude::*, system::SystemParam};
+use crossbeam::queue::SegQueue;
+
+/// A multi-producer queue where every registered reader sees every message.
+///
+/// Pushing is lock-free. Each reader keeps its own cursor, and messages are
+/// reclaimed once every registered reader has read them. Readers are
+/// registered with [`BroadcastQueue::register`], or automatically for the
+/// lifetime of a system when using a [`BroadcastReader`].
+///
+/// Messages pushed while no reader is registered are kept for the readers
+/// registered next, since Bevy only initializes reader systems when their
+/// schedule first runs. Every reader registered before any of them reads sees
+/// those messages.
+#[derive(Resource)]
+pub struct BroadcastQueue<T> {
+    incoming: SegQueue<T>,
+    shared: Arc<Mutex<Broadcast<T>>>,
+}
+
+struct Broadcast<T> {
+    messages: VecDeque<T>,
+    /// Index of the first message in `messages`.
+    start: u64,
+    /// Index of the next message to be read by each reader, or `None` for
+    /// slots whose reader has been dropped.
+    cursors: Vec<Option<u64>>,
+    /// Whether no reader has read since the last time there were none, so new
+    /// readers should start from the oldest kept message.
+    unread: bool,
+}
+
+impl<T> Broadcast<T> {
+    fn end(&self) -> u64 {
+        self.start + self.messages.len() as u64
+    }
+
+    /// Drop messages that every registered reader has read, keeping every
+    /// message if there are no registered readers.
+    fn reclaim(&mut self) {
+        let Some(oldest) = self.cursors.iter().flatten().copied().min() else {
+            self.unread = true;
+            return;
+        };
+        let read = (oldest - self.start) as usize;
+        self.messages.drain(..read);
+        self.start = oldest;
+    }
+}
+
+impl<T> Default for BroadcastQueue<T> {
+    fn default() -> Self {
+        Self {
+            incoming: Default::default(),
+            shared: Arc::new(Mutex::new(Broadcast {
+                messages: Default::default(),
+                start: 0,
+                cursors: Default::default(),
+                unread: true,
+            })),
+        }
+    }
+}
+
+impl<T> BroadcastQueue<T> {
+    /// Create a new queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Push an item onto the queue.
+    pub fn push(&self, value: T) {
+        self.incoming.push(value)
+    }
+
+    /// Register a new reader. The reader will see every message pushed after
+    /// it was registered, as well as any kept from before while no reader had
+    /// read them, and is unregistered when the cursor is dropped.
+    pub fn register(&self) -> BroadcastCursor<T> {
+        let mut shared = self.lock();
+        let from = if shared.unread {
+            shared.start
+        } else {
+            shared.end()
+        };
+        let id = match shared.cursors.iter().position(Option::is_none) {
+            Some(id) => {
+                shared.cursors[id] = Some(from);
+                id
+            }
+            None => {
+                shared.cursors.push(Some(from));
+                shared.cursors.len() - 1
+            }
+        };
+        BroadcastCursor {
+            id,
+            shared: self.shared.clone(),
+        }
+    }
+
+    /// Test if the reader with the given cursor has no unread messages.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cursor was registered with a different queue.
+    pub fn is_empty(&self, cursor: &BroadcastCursor<T>) -> bool {
+        self.check(cursor);
+        let shared = self.lock();
+        shared.cursors[cursor.id] == Some(shared.end())
+    }
+
+    /// Read every message the reader with the given cursor has not yet seen.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cursor was registered with a different queue.
+    pub fn read(&self, cursor: &BroadcastCursor<T>) -> std::vec::IntoIter<T>
+    where
+        T: Clone,
+    {
+        self.check(cursor);
+        let mut shared = self.lock();
+        shared.unread = false;
+        let end = shared.end();
+        let from = shared.cursors[cursor.id]
+            .replace(end)
+            .expect("cursor is registered while it is alive");
+        let start = (from - shared.start) as usize;
+        let messages: Vec<T> = shared.messages.range(start..).cloned().collect();
+        shared.reclaim();
+        messages.into_iter()
+    }
+
+    fn check(&self, cursor: &BroadcastCursor<T>) {
+        assert!(
+            Arc::ptr_eq(&self.shared, &cursor.shared),
+            "cursor for `BroadcastQueue<{}>` was registered with a different queue",
+            std::any::type_name::<T>()
+        );
+    }
+
+    /// Lock the shared state, first moving newly pushed items into it so that
+    /// they are visible to readers.
+    fn lock(&self) -> MutexGuard<'_, Broadcast<T>> {
+        let mut shared = self.shared.lock().unwrap();
+        while let Some(value) = self.incoming.pop() {
+            shared.messages.push_back(value);
+        }
+        shared.reclaim();
+        shared
+    }
+}
+
+/// The position of a single reader in a [`BroadcastQueue`].
+///
+/// Created by calling [`BroadcastQueue::register`]. The reader is unregistered
+/// when the cursor is dropped.
+pub struct BroadcastCursor<T> {
+    id: usize,
+    shared: Arc<Mutex<Broadcast<T>>>,
+}
+
+impl<T> Drop for BroadcastCursor<T> {
+    fn drop(&mut self) {
+        if let Ok(mut shared) = self.shared.lock() {
+            shared.cursors[self.id] = None;
+            shared.reclaim();
+        }
+    }
+}
+
+impl<T> FromWorld for BroadcastCursor<T>
+where
+    T: Send + 'static,
+{
+    fn from_world(world: &mut World) -> Self {
+        match world.get_resource::<BroadcastQueue<T>>() {
+            Some(queue) => queue.register(),
+            None => panic!(
+                "Unable to read from broadcast queue `{}`\n\tQueue must be added to the world before any reader systems are initialized.",
+                std::any::type_name::<T>()
+            ),
+        }
+    }
+}
+
+/// Push items onto a `BroadcastQueue`.
+///
+/// Used as a convenience `SystemParam` rather than operating on the underlying
+/// [`BroadcastQueue`] directly.
+#[derive(SystemParam)]
+pub struct BroadcastWriter<'w, 's, E>
+where
+    E: Send + 'static,
+{
+    queue: Res<'w, BroadcastQueue<E>>,
+    #[system_param(ignore)]
+    marker: PhantomData<&'s ()>,
+}
+
+impl<'w, 's, E> BroadcastWriter<'w, 's, E>
+where
+    E: Send + 'static,
+{
+    /// Push an item onto the `BroadcastQueue`.
+    pub fn push(&self, value: E) {
+        self.queue.push(value)
+    }
+}
+
+/// Read items from a `BroadcastQueue`.
+///
+/// Each system using a `BroadcastReader` is registered as a separate reader
+/// when it is initialized, and sees every message pushed after that exactly
+/// once.
+#[derive(SystemParam)]
+pub struct BroadcastReader<'w, 's, E>
+where
+    E: Send + 'static,
+{
+    queue: Res<'w, BroadcastQueue<E>>,
+    cursor: Local<'s, BroadcastCursor<E>>,
+}
+
+impl<'w, 's, E> BroadcastReader<'w, 's, E>
+where
+    E: Send + 'static,
+{
+    /// Check if there are no unread messages.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty(&self.cursor)
+    }
+
+    /// Iterate over every message this reader has not yet seen.
+    pub fn read(&self) -> std::vec::IntoIter<E>
+    where
+        E: Clone,
+    {
+        self.queue.read(&self.cursor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::schedule::Schedule;
+
+    use super::*;
+
+    #[test]
+    fn every_reader_sees_every_message() {
+        let queue = BroadcastQueue::new();
+        let (a, b) = (queue.register(), queue.register());
+        queue.push(1);
+        queue.push(2);
+        assert_eq!(queue.read(&a).collect::<Vec<_>>(), [1, 2]);
+        queue.push(3);
+        assert_eq!(queue.read(&a).collect::<Vec<_>>(), [3]);
+        assert_eq!(queue.read(&b).collect::<Vec<_>>(), [1, 2, 3]);
+        assert!(queue.is_empty(&a) && queue.is_empty(&b));
+    }
+
+    #[test]
+    fn keeps_messages_until_a_reader_registers() {
+        let queue = BroadcastQueue::new();
+        queue.push(1);
+        let (a, b) = (queue.register(), queue.register());
+        assert_eq!(queue.read(&a).collect::<Vec<_>>(), [1]);
+        assert_eq!(queue.read(&b).collect::<Vec<_>>(), [1]);
+
+        // Once read, later readers only see new messages.
+        let c = queue.register();
+        assert!(queue.is_empty(&c));
+
+        drop((a, b, c));
+        queue.push(2);
+        let d = queue.register();
+        assert_eq!(queue.read(&d).collect::<Vec<_>>(), [2]);
+    }
+
+    #[test]
+    fn keeps_messages_pushed_before_readers_initialize() {
+        #[derive(Resource, Default)]
+        struct Seen(Vec<u32>);
+
+        let mut world = World::new();
+        world.init_resource::<BroadcastQueue<u32>>();
+        world.init_resource::<Seen>();
+        world.resource::<BroadcastQueue<u32>>().push(7);
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(|reader: BroadcastReader<u32>, mut seen: ResMut<Seen>| {
+            seen.0.extend(reader.read());
+        });
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<Seen>().0, [7]);
+    }
+
+    #[test]
+    #[should_panic(expected = "registered with a different queue")]
+    fn rejects_cursors_from_other_queues() {
+        let (a, b) = (BroadcastQueue::<u32>::new(), BroadcastQueue::<u32>::new());
+        let cursor = a.register();
+        b.is_empty(&cursor);
+    }
+}
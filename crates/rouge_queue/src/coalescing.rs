@@ -0,0 +1,230 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::sync::Mutex;
+
+use bevy_ecs::{prelude::*, system::SystemParam};
+
+/// A queue that holds at most one pending item per key.
+///
+/// Pushing an item with the same key as a pending item replaces it, keeping
+/// the pending item's place in the queue, so items are popped in the order
+/// their keys were first pushed. Useful for messages where only the newest one
+/// matters, such as marking an entity's field of view as dirty.
+#[derive(Resource)]
+pub struct CoalescingQueue<K, T> {
+    inner: Mutex<Coalesced<K, T>>,
+}
+
+struct Coalesced<K, T> {
+    items: HashMap<K, (u64, T)>,
+    order: BTreeMap<u64, K>,
+    next: u64,
+    replaced: usize,
+}
+
+impl<K, T> Default for CoalescingQueue<K, T> {
+    fn default() -> Self {
+        Self {
+            inner: Mutex::new(Coalesced {
+                items: HashMap::new(),
+                order: BTreeMap::new(),
+                next: 0,
+                replaced: 0,
+            }),
+        }
+    }
+}
+
+impl<K, T> CoalescingQueue<K, T>
+where
+    K: Eq + Hash + Clone,
+{
+    /// Create a new, empty queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of keys with a pending item.
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().items.len()
+    }
+
+    /// Test if the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.inner.lock().unwrap().items.is_empty()
+    }
+
+    /// Test if an item with the given key is pending.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.inner.lock().unwrap().items.contains_key(key)
+    }
+
+    /// The total number of pending items replaced by newer items with the
+    /// same key.
+    pub fn replaced(&self) -> usize {
+        self.inner.lock().unwrap().replaced
+    }
+
+    /// Push an item onto the queue, replacing any pending item with the same
+    /// key. Returns the replaced item.
+    pub fn push(&self, key: K, value: T) -> Option<T> {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some((_, pending)) = inner.items.get_mut(&key) {
+            let replaced = std::mem::replace(pending, value);
+            inner.replaced += 1;
+            return Some(replaced);
+        }
+        let seq = inner.next;
+        inner.next += 1;
+        inner.order.insert(seq, key.clone());
+        inner.items.insert(key, (seq, value));
+        None
+    }
+
+    /// Pop the item whose key was pushed first. Returns `None` if the queue is
+    /// empty.
+    pub fn pop(&self) -> Option<(K, T)> {
+        let mut inner = self.inner.lock().unwrap();
+        let (_, key) = inner.order.pop_first()?;
+        let (_, value) = inner.items.remove(&key)?;
+        Some((key, value))
+    }
+
+    /// Remove the pending item with the given key, if any.
+    pub fn remove(&self, key: &K) -> Option<T> {
+        let mut inner = self.inner.lock().unwrap();
+        let (seq, value) = inner.items.remove(key)?;
+        inner.order.remove(&seq);
+        Some(value)
+    }
+
+    /// Iterate over items in the queue. This drains the queue, but does not
+    /// consume the `CoalescingQueue` itself.
+    pub fn iter(&self) -> CoalescingQueueIter<'_, K, T> {
+        CoalescingQueueIter { q: self }
+    }
+}
+
+/// An iterator for `CoalescingQueue`.
+///
+/// Created by calling [`CoalescingQueue::iter`]. See its documentation for
+/// more.
+pub struct CoalescingQueueIter<'a, K, T> {
+    q: &'a CoalescingQueue<K, T>,
+}
+
+impl<'a, K, T> Iterator for CoalescingQueueIter<'a, K, T>
+where
+    K: Eq + Hash + Clone,
+{
+    type Item = (K, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.q.pop()
+    }
+}
+
+/// Push items onto a `CoalescingQueue`.
+///
+/// Used as a convenience `SystemParam` rather than operating on the underlying
+/// [`CoalescingQueue`] directly.
+#[derive(SystemParam)]
+pub struct CoalescingQueueWriter<'w, 's, K, E>
+where
+    K: Eq + Hash + Clone + Send + 'static,
+    E: Send + 'static,
+{
+    queue: Res<'w, CoalescingQueue<K, E>>,
+    #[system_param(ignore)]
+    marker: PhantomData<&'s ()>,
+}
+
+impl<'w, 's, K, E> CoalescingQueueWriter<'w, 's, K, E>
+where
+    K: Eq + Hash + Clone + Send + 'static,
+    E: Send + 'static,
+{
+    /// Push an item onto the `CoalescingQueue`, replacing any pending item
+    /// with the same key.
+    pub fn push(&self, key: K, value: E) {
+        self.queue.push(key, value);
+    }
+}
+
+/// Pop items from a `CoalescingQueue`.
+///
+/// Used as a convenience `SystemParam` rather than operating on the underlying
+/// [`CoalescingQueue`] directly.
+#[derive(SystemParam)]
+pub struct CoalescingQueueReader<'w, 's, K, E>
+where
+    K: Eq + Hash + Clone + Send + 'static,
+    E: Send + 'static,
+{
+    queue: Res<'w, CoalescingQueue<K, E>>,
+    #[system_param(ignore)]
+    marker: PhantomData<&'s ()>,
+}
+
+impl<'w, 's, K, E> CoalescingQueueReader<'w, 's, K, E>
+where
+    K: Eq + Hash + Clone + Send + 'static,
+    E: Send + 'static,
+{
+    /// Check if the queue is empty without modifying the contents of the
+    /// `CoalescingQueue`.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Pop the item whose key was pushed first.
+    pub fn pop(&self) -> Option<(K, E)> {
+        self.queue.pop()
+    }
+
+    /// Iterate over through items in the `CoalescingQueue`, in the order their
+    /// keys were first pushed. This drains the `queue`, but does not consume
+    /// the underlying `CoalescingQueue`.
+    pub fn iter(&self) -> CoalescingQueueIter<'_, K, E> {
+        self.queue.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_latest_value_at_first_position() {
+        let queue = CoalescingQueue::new();
+        assert_eq!(queue.push("a", 1), None);
+        assert_eq!(queue.push("b", 2), None);
+        assert_eq!(queue.push("a", 3), Some(1));
+        assert_eq!(queue.push("c", 4), None);
+        assert_eq!(queue.push("a", 5), Some(3));
+        assert_eq!(queue.push("b", 6), Some(2));
+
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.replaced(), 3);
+        assert_eq!(
+            queue.iter().collect::<Vec<_>>(),
+            [("a", 5), ("b", 6), ("c", 4)]
+        );
+    }
+
+    #[test]
+    fn popped_keys_go_to_the_back() {
+        let queue = CoalescingQueue::new();
+        queue.push("a", 1);
+        queue.push("b", 2);
+        assert_eq!(queue.pop(), Some(("a", 1)));
+        queue.push("a", 3);
+        assert_eq!(queue.remove(&"b"), Some(2));
+        queue.push("b", 4);
+
+        assert!(!queue.contains_key(&"c"));
+        assert_eq!(queue.iter().collect::<Vec<_>>(), [("a", 3), ("b", 4)]);
+        assert!(queue.is_empty());
+    }
+}
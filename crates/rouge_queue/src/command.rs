@@ -0,0 +1,90 @@
+use bevy_ecs::prelude::*;
+use tracing::warn;
+
+use crate::{Queue, QueueSender, QueueWriter};
+
+/// A command applied to the `World` by [`apply_world_commands`].
+///
+/// Unlike Bevy's `Commands`, these can be pushed from anywhere a
+/// [`Queue<WorldCommand>`] can be reached, including other threads and `&World`
+/// contexts, without exclusive access.
+pub type WorldCommand = Box<dyn FnOnce(&mut World) + Send>;
+
+/// Trait for pushing [`WorldCommand`]s.
+pub trait WorldCommandsExt {
+    /// Push a closure to be applied to the `World`.
+    fn add<F>(&self, command: F)
+    where
+        F: FnOnce(&mut World) + Send + 'static;
+
+    /// Spawn an entity with the given bundle.
+    fn spawn<B: Bundle>(&self, bundle: B) {
+        self.add(move |world: &mut World| {
+            world.spawn(bundle);
+        });
+    }
+
+    /// Insert a bundle into the given entity, if it still exists.
+    fn insert<B: Bundle>(&self, entity: Entity, bundle: B) {
+        self.add(
+            move |world: &mut World| match world.get_entity_mut(entity) {
+                Some(mut entity) => {
+                    entity.insert(bundle);
+                }
+                None => warn!(
+                    "Unable to insert `{}` into entity {:?} because it does not exist",
+                    std::any::type_name::<B>(),
+                    entity
+                ),
+            },
+        );
+    }
+
+    /// Despawn the given entity, if it still exists.
+    fn despawn(&self, entity: Entity) {
+        self.add(move |world: &mut World| {
+            world.despawn(entity);
+        });
+    }
+}
+
+impl WorldCommandsExt for Queue<WorldCommand> {
+    fn add<F>(&self, command: F)
+    where
+        F: FnOnce(&mut World) + Send + 'static,
+    {
+        self.push(Box::new(command));
+    }
+}
+
+impl WorldCommandsExt for QueueSender<WorldCommand> {
+    fn add<F>(&self, command: F)
+    where
+        F: FnOnce(&mut World) + Send + 'static,
+    {
+        self.push(Box::new(command));
+    }
+}
+
+impl<'w, 's> WorldCommandsExt for QueueWriter<'w, 's, WorldCommand> {
+    fn add<F>(&self, command: F)
+    where
+        F: FnOnce(&mut World) + Send + 'static,
+    {
+        self.push(Box::new(command));
+    }
+}
+
+/// Exclusive system that applies every [`WorldCommand`] in the queue to the
+/// `World`, in the order they were pushed.
+///
+/// Commands pushed while applying are left for the next run.
+pub fn apply_world_commands(world: &mut World) {
+    let Some(queue) = world.get_resource::<Queue<WorldCommand>>() else {
+        return;
+    };
+    let commands: Vec<WorldCommand> = queue.iter().collect();
+    for command in commands {
+        command(world);
+    }
+}
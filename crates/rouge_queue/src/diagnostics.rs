@@ -0,0 +1,98 @@
+use std::marker::PhantomData;
+
+use bevy_app::{App, Last};
+use bevy_diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic};
+use bevy_ecs::prelude::*;
+use tracing::warn;
+
+use crate::{Queue, QueueSet, QueueStats};
+
+/// Configuration for the diagnostics of queues added with the `QueuePlugin`.
+#[derive(Resource)]
+pub struct QueueDiagnosticsConfig {
+    /// Warn when a queue has gone this many frames without being drained.
+    /// `None` disables the warning.
+    pub undrained_frames: Option<u32>,
+}
+
+impl Default for QueueDiagnosticsConfig {
+    fn default() -> Self {
+        Self {
+            undrained_frames: Some(120),
+        }
+    }
+}
+
+/// Paths of the diagnostics for a [`Queue`] of type `T`, under the type name.
+pub struct QueueDiagnosticPaths<T> {
+    /// Items pushed during the frame.
+    pub pushes: DiagnosticPath,
+    /// Items popped during the frame.
+    pub pops: DiagnosticPath,
+    /// Items in the queue at the end of the frame.
+    pub len: DiagnosticPath,
+    /// The largest number of items the queue has held at once.
+    pub max_len: DiagnosticPath,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for QueueDiagnosticPaths<T> {
+    fn default() -> Self {
+        let path =
+            |name| DiagnosticPath::from_components(["queue", std::any::type_name::<T>(), name]);
+        Self {
+            pushes: path("pushes"),
+            pops: path("pops"),
+            len: path("len"),
+            max_len: path("max_len"),
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Register the diagnostics for a [`Queue`] of type `T`, and measure them every
+/// frame.
+pub(crate) fn add_queue_diagnostics<T: Send + 'static>(app: &mut App) {
+    let paths = QueueDiagnosticPaths::<T>::default();
+    app.init_resource::<QueueDiagnosticsConfig>()
+        .register_diagnostic(Diagnostic::new(paths.pushes))
+        .register_diagnostic(Diagnostic::new(paths.pops))
+        .register_diagnostic(Diagnostic::new(paths.len))
+        .register_diagnostic(Diagnostic::new(paths.max_len))
+        .add_systems(Last, measure_queue::<T>.after(QueueSet::<T>::Read));
+}
+
+#[derive(Default)]
+struct MeasureState {
+    last: QueueStats,
+    undrained_frames: u32,
+}
+
+fn measure_queue<T: Send + 'static>(
+    queue: Res<Queue<T>>,
+    config: Res<QueueDiagnosticsConfig>,
+    mut diagnostics: Diagnostics,
+    paths: Local<QueueDiagnosticPaths<T>>,
+    mut state: Local<MeasureState>,
+) {
+    let stats = queue.stats();
+    let last = std::mem::replace(&mut state.last, stats);
+    diagnostics.add_measurement(&paths.pushes, || (stats.pushes - last.pushes) as f64);
+    diagnostics.add_measurement(&paths.pops, || (stats.pops - last.pops) as f64);
+    diagnostics.add_measurement(&paths.len, || stats.len as f64);
+    diagnostics.add_measurement(&paths.max_len, || stats.max_len as f64);
+
+    if queue.take_drained() || queue.is_empty() {
+        state.undrained_frames = 0;
+        return;
+    }
+    state.undrained_frames += 1;
+    if Some(state.undrained_frames) == config.undrained_frames {
+        warn!(
+            "Queue `{}` has not been drained for {} frames ({} items pending)",
+            std::any::type_name::<T>(),
+            state.undrained_frames,
+            stats.len
+        );
+    }
+}
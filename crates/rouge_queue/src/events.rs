@@ -0,0 +1,153 @@
+#[cfg(feature = "app")]
+use std::collections::VecDeque;
+#[cfg(feature = "app")]
+use std::ops::Range;
+#[cfg(feature = "app")]
+use std::sync::Arc;
+
+use bevy_ecs::prelude::*;
+#[cfg(feature = "app")]
+use crossbeam::queue::SegQueue;
+
+#[cfg(feature = "app")]
+use crate::queue::ForwarderId;
+use crate::Queue;
+
+/// Which way a [`Queue`] is bridged to Bevy's `Events` of the same type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EventBridgeDirection {
+    /// Drain the queue and send its items as events.
+    QueueToEvents,
+    /// Push every event onto the queue.
+    EventsToQueue,
+    /// Send every item pushed onto the queue as an event, and push every event
+    /// onto the queue, without draining either. Items cross the bridge once,
+    /// and are never forwarded back to where they came from.
+    Both,
+}
+
+/// System that drains the [`Queue`] of type `T` and sends its items as events.
+pub fn queue_to_events<T: Event>(queue: Res<Queue<T>>, mut events: EventWriter<T>) {
+    events.send_batch(queue.iter());
+}
+
+/// System that pushes every new event of type `T` onto the [`Queue`] of the
+/// same type.
+pub fn events_to_queue<T: Event + Clone>(mut events: EventReader<T>, queue: Res<Queue<T>>) {
+    for event in events.read() {
+        queue.push(event.clone());
+    }
+}
+
+/// State of a [`EventBridgeDirection::Both`] bridge: items pushed onto the
+/// queue that are waiting to be sent as events, and the ids of the events sent
+/// from the queue, so they aren't pushed back onto it.
+#[cfg(feature = "app")]
+#[derive(Resource)]
+pub(crate) struct TwoWayBridge<T> {
+    id: ForwarderId,
+    outgoing: Arc<SegQueue<T>>,
+    sent: VecDeque<Range<usize>>,
+}
+
+#[cfg(feature = "app")]
+impl<T: Event + Clone> TwoWayBridge<T> {
+    /// Start copying the items pushed onto `queue`, except those pushed from
+    /// events, so they can be sent as events.
+    pub(crate) fn new(queue: &Queue<T>) -> Self {
+        let id = ForwarderId::new();
+        let outgoing = Arc::new(SegQueue::new());
+        let forward = outgoing.clone();
+        queue.add_forwarder(id, Box::new(move |value: &T| forward.push(value.clone())));
+        Self {
+            id,
+            outgoing,
+            sent: VecDeque::new(),
+        }
+    }
+
+    /// Test if the event with the given id was sent from the queue. Ids must
+    /// be checked in increasing order.
+    fn was_sent(&mut self, id: usize) -> bool {
+        while self.sent.front().is_some_and(|sent| sent.end <= id) {
+            self.sent.pop_front();
+        }
+        self.sent.front().is_some_and(|sent| sent.contains(&id))
+    }
+}
+
+/// System that sends the items pushed onto a both-ways bridged [`Queue`] as
+/// events.
+#[cfg(feature = "app")]
+pub(crate) fn forward_queue_to_events<T: Event + Clone>(
+    mut bridge: ResMut<TwoWayBridge<T>>,
+    mut events: EventWriter<T>,
+) {
+    let mut ids = events.send_batch(std::iter::from_fn(|| bridge.outgoing.pop()));
+    if let Some(first) = ids.next() {
+        let last = ids.last().unwrap_or(first);
+        bridge.sent.push_back(first.id..last.id + 1);
+    }
+}
+
+/// System that pushes the events of type `T` onto a both-ways bridged
+/// [`Queue`], except those sent from the queue.
+#[cfg(feature = "app")]
+pub(crate) fn forward_events_to_queue<T: Event + Clone>(
+    mut events: EventReader<T>,
+    mut bridge: ResMut<TwoWayBridge<T>>,
+    queue: Res<Queue<T>>,
+) {
+    for (event, id) in events.read_with_id() {
+        if !bridge.was_sent(id.id) {
+            queue.push_received(event.clone(), bridge.id);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "app"))]
+mod tests {
+    use bevy_app::{App, First, Update};
+
+    use super::*;
+    use crate::{QueuePlugin, QueueSet};
+
+    #[derive(Event, Clone, Debug, PartialEq)]
+    struct Message(u32);
+
+    #[derive(Resource, Default)]
+    struct Seen(Vec<u32>);
+
+    fn read_events(mut events: EventReader<Message>, mut seen: ResMut<Seen>) {
+        seen.0.extend(events.read().map(|Message(i)| i));
+    }
+
+    #[test]
+    fn both_ways_items_cross_once() {
+        let mut app = App::new();
+        app.add_plugins(
+            QueuePlugin::default().bridge_events::<Message>(EventBridgeDirection::Both, Update),
+        )
+        .init_resource::<Seen>()
+        .add_systems(
+            First,
+            |mut events: EventWriter<Message>, mut sent: Local<bool>| {
+                if !std::mem::replace(&mut *sent, true) {
+                    events.send(Message(1));
+                }
+            },
+        )
+        .add_systems(Update, read_events.after(QueueSet::<Message>::Read));
+
+        app.world.resource::<Queue<Message>>().push(Message(2));
+        for _ in 0..4 {
+            app.update();
+        }
+
+        let mut seen = app.world.resource::<Seen>().0.clone();
+        seen.sort_unstable();
+        assert_eq!(seen, [1, 2]);
+        let queued: Vec<_> = app.world.resource::<Queue<Message>>().iter().collect();
+        assert_eq!(queued, [Message(2), Message(1)]);
+    }
+}
@@ -1,4 +1,46 @@
+mod add_queue;
+mod bounded;
+mod broadcast;
+mod coalescing;
+mod command;
+#[cfg(feature = "app")]
+mod diagnostics;
+mod events;
+mod mailbox;
+mod merge;
+#[cfg(feature = "net")]
+mod net;
+#[cfg(feature = "app")]
 mod plugin;
+mod priority;
 mod queue;
+#[cfg(feature = "record")]
+mod record;
+mod request;
+mod scheduler;
+mod set;
+#[cfg(feature = "trace")]
+mod trace;
+pub use add_queue::*;
+pub use bounded::*;
+pub use broadcast::*;
+pub use coalescing::*;
+pub use command::*;
+#[cfg(feature = "app")]
+pub use diagnostics::*;
+pub use events::*;
+pub use mailbox::*;
+pub use merge::*;
+#[cfg(feature = "net")]
+pub use net::*;
+#[cfg(feature = "app")]
 pub use plugin::*;
+pub use priority::*;
 pub use queue::*;
+#[cfg(feature = "record")]
+pub use record::*;
+pub use request::*;
+pub use scheduler::*;
+pub use set::*;
+#[cfg(feature = "trace")]
+pub use trace::*;
@@ -0,0 +1,121 @@
+use std::fmt;
+
+use bevy_ecs::{prelude::*, system::SystemParam};
+use crossbeam::queue::SegQueue;
+
+/// A queue of messages for a single entity.
+///
+/// Messages are sent to an entity with [`MailboxWriter`], and read by
+/// draining the mailbox in a query, such as `Query<&Mailbox<Hit>>`. Since the
+/// mailbox is a component, its pending messages are dropped with it when the
+/// entity is despawned.
+#[derive(Component)]
+pub struct Mailbox<T: Send + 'static> {
+    q: SegQueue<T>,
+}
+
+impl<T: Send + 'static> Default for Mailbox<T> {
+    fn default() -> Self {
+        Self { q: SegQueue::new() }
+    }
+}
+
+impl<T: Send + 'static> Mailbox<T> {
+    /// Create a new, empty mailbox.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of messages in the mailbox.
+    pub fn len(&self) -> usize {
+        self.q.len()
+    }
+
+    /// Test if the mailbox is empty.
+    pub fn is_empty(&self) -> bool {
+        self.q.is_empty()
+    }
+
+    /// Push a message into the mailbox.
+    pub fn push(&self, value: T) {
+        self.q.push(value)
+    }
+
+    /// Pop the oldest message from the mailbox. Returns `None` if it is empty.
+    pub fn pop(&self) -> Option<T> {
+        self.q.pop()
+    }
+
+    /// Iterate over messages in the mailbox. This drains the mailbox, but does
+    /// not consume the `Mailbox` itself.
+    pub fn iter(&self) -> MailboxIter<'_, T> {
+        MailboxIter { mailbox: self }
+    }
+}
+
+/// An iterator for `Mailbox`.
+///
+/// Created by calling [`Mailbox::iter`]. See its documentation for more.
+pub struct MailboxIter<'a, T: Send + 'static> {
+    mailbox: &'a Mailbox<T>,
+}
+
+impl<'a, T: Send + 'static> Iterator for MailboxIter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.mailbox.pop()
+    }
+}
+
+/// Error returned when sending a message to an entity that doesn't exist, or
+/// has no [`Mailbox`] for the message type. Contains the undelivered message.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Undeliverable<T>(pub T);
+
+impl<T> fmt::Display for Undeliverable<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "no `Mailbox<{}>` for the entity",
+            std::any::type_name::<T>()
+        )
+    }
+}
+
+impl<T: fmt::Debug> std::error::Error for Undeliverable<T> {}
+
+/// Send messages to the [`Mailbox`]es of entities.
+///
+/// Only needs read access to the mailboxes, so systems sending messages of the
+/// same type can run in parallel.
+#[derive(SystemParam)]
+pub struct MailboxWriter<'w, 's, E>
+where
+    E: Send + 'static,
+{
+    mailboxes: Query<'w, 's, &'static Mailbox<E>>,
+}
+
+impl<'w, 's, E> MailboxWriter<'w, 's, E>
+where
+    E: Send + 'static,
+{
+    /// Send a message to the given entity. Returns the message as an
+    /// [`Undeliverable`] error if the entity doesn't have a `Mailbox`, for
+    /// example because it has been despawned.
+    pub fn send(&self, entity: Entity, value: E) -> Result<(), Undeliverable<E>> {
+        match self.mailboxes.get(entity) {
+            Ok(mailbox) => {
+                mailbox.push(value);
+                Ok(())
+            }
+            Err(_) => Err(Undeliverable(value)),
+        }
+    }
+
+    /// Test if the given entity has a `Mailbox` that can receive messages.
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.mailboxes.contains(entity)
+    }
+}
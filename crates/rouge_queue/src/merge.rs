@@ -0,0 +1,205 @@
+use std::marker::PhantomData;
+
+use bevy_ecs::prelude::*;
+use bevy_ecs::system::{ReadOnlySystemParam, StaticSystemParam, SystemParam, SystemParamItem};
+use bevy_ecs::world::FromWorld;
+
+use crate::Queue;
+
+/// A tuple of [`Queue`] item types that can be read together as a single stream
+/// of `M`s by a [`MergedQueueReader`].
+///
+/// Implemented for tuples of up to eight types that each convert into `M`.
+pub trait MergeQueues<M>: 'static {
+    /// The resources read to drain the queues.
+    type Param: ReadOnlySystemParam;
+
+    /// Drain every queue into `out`, with the stamp ordering each item's push.
+    fn drain(queues: &SystemParamItem<'_, '_, Self::Param>, out: &mut Vec<(u64, M)>);
+}
+
+/// State of a [`MergedQueueReader`] that enables stamping on the queues of `Q`
+/// when the reader's system is initialized, so that their pushes can be put in
+/// order. Pushes onto queues that aren't read by a merged reader aren't
+/// stamped.
+pub struct EnableStamping<Q>(PhantomData<fn() -> Q>);
+
+macro_rules! impl_merge_queues {
+    ($($t:ident),*) => {
+        impl<M, $($t),*> MergeQueues<M> for ($($t,)*)
+        where
+            $($t: Into<M> + Send + 'static,)*
+        {
+            type Param = (
+                ($(Res<'static, Queue<$t>>,)*),
+                Local<'static, EnableStamping<($($t,)*)>>,
+            );
+
+            #[allow(non_snake_case)]
+            fn drain(queues: &SystemParamItem<'_, '_, Self::Param>, out: &mut Vec<(u64, M)>) {
+                let (($($t,)*), _) = queues;
+                $(
+                    // Queues added after the reader was initialized.
+                    $t.enable_stamping();
+                    while let Some((stamp, value)) = $t.pop_stamped() {
+                        out.push((stamp, value.into()));
+                    }
+                )*
+            }
+        }
+
+        impl<$($t: Send + 'static),*> FromWorld for EnableStamping<($($t,)*)> {
+            fn from_world(world: &mut World) -> Self {
+                $(
+                    if let Some(queue) = world.get_resource::<Queue<$t>>() {
+                        queue.enable_stamping();
+                    }
+                )*
+                Self(PhantomData)
+            }
+        }
+    };
+}
+
+impl_merge_queues!(A);
+impl_merge_queues!(A, B);
+impl_merge_queues!(A, B, C);
+impl_merge_queues!(A, B, C, D);
+impl_merge_queues!(A, B, C, D, E);
+impl_merge_queues!(A, B, C, D, E, F);
+impl_merge_queues!(A, B, C, D, E, F, G);
+impl_merge_queues!(A, B, C, D, E, F, G, H);
+
+/// Pop items from several `Queue`s at once, in the order they were pushed
+/// across all of them.
+///
+/// `Q` is a tuple of the queues' item types, each converted into `M`, which is
+/// usually an enum with a variant for each queue:
+///
+/// ```
+/// # use rouge_queue::MergedQueueReader;
+/// # struct Attack;
+/// # struct Heal;
+/// enum Combat {
+///     Attack(Attack),
+///     Heal(Heal),
+/// }
+/// # impl From<Attack> for Combat {
+/// #     fn from(attack: Attack) -> Self { Combat::Attack(attack) }
+/// # }
+/// # impl From<Heal> for Combat {
+/// #     fn from(heal: Heal) -> Self { Combat::Heal(heal) }
+/// # }
+///
+/// fn resolve(reader: MergedQueueReader<Combat, (Attack, Heal)>) {
+///     for message in reader.iter() {
+///         // ...
+///     }
+/// }
+/// ```
+///
+/// Pushes are put in order once the reader's system has been initialized, which
+/// enables stamping on the queues, or once [`Queue::enable_stamping`] has been
+/// called. Items pushed from other threads while the queues are being drained
+/// may be left for the next read.
+#[derive(SystemParam)]
+pub struct MergedQueueReader<'w, 's, M, Q>
+where
+    M: 'static,
+    Q: MergeQueues<M>,
+{
+    queues: StaticSystemParam<'w, 's, <Q as MergeQueues<M>>::Param>,
+    #[system_param(ignore)]
+    marker: PhantomData<fn() -> M>,
+}
+
+impl<'w, 's, M, Q> MergedQueueReader<'w, 's, M, Q>
+where
+    M: 'static,
+    Q: MergeQueues<M>,
+{
+    /// Iterate over the items in every queue, in the order they were pushed.
+    /// This drains the queues, but does not consume the underlying `Queue`s.
+    pub fn iter(&self) -> impl Iterator<Item = M> {
+        let mut items = Vec::new();
+        Q::drain(&self.queues, &mut items);
+        // Items pushed before stamping was enabled all have a stamp of 0, and
+        // are kept in the order of their own queue.
+        items.sort_by_key(|(stamp, _)| *stamp);
+        items.into_iter().map(|(_, item)| item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::system::System;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum Combat {
+        Attack(u32),
+        Heal(u32),
+    }
+
+    struct Attack(u32);
+    struct Heal(u32);
+
+    impl From<Attack> for Combat {
+        fn from(Attack(i): Attack) -> Self {
+            Combat::Attack(i)
+        }
+    }
+
+    impl From<Heal> for Combat {
+        fn from(Heal(i): Heal) -> Self {
+            Combat::Heal(i)
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct Resolved(Vec<Combat>);
+
+    fn resolve(reader: MergedQueueReader<Combat, (Attack, Heal)>, mut resolved: ResMut<Resolved>) {
+        resolved.0.extend(reader.iter());
+    }
+
+    #[test]
+    fn reads_in_push_order_across_queues() {
+        let mut world = World::new();
+        world.init_resource::<Queue<Attack>>();
+        world.init_resource::<Queue<Heal>>();
+        world.init_resource::<Resolved>();
+        let mut system = IntoSystem::into_system(resolve);
+        system.initialize(&mut world);
+
+        for i in 0..3 {
+            world.resource::<Queue<Attack>>().push(Attack(i));
+            world.resource::<Queue<Heal>>().push(Heal(i));
+        }
+        world.resource::<Queue<Heal>>().push(Heal(3));
+        world.resource::<Queue<Attack>>().push(Attack(3));
+        system.run((), &mut world);
+
+        assert_eq!(
+            world.resource::<Resolved>().0,
+            [
+                Combat::Attack(0),
+                Combat::Heal(0),
+                Combat::Attack(1),
+                Combat::Heal(1),
+                Combat::Attack(2),
+                Combat::Heal(2),
+                Combat::Heal(3),
+                Combat::Attack(3),
+            ]
+        );
+    }
+
+    #[test]
+    fn other_queues_are_not_stamped() {
+        let queue = Queue::new();
+        queue.push(1);
+        assert_eq!(queue.pop_stamped(), Some((0, 1)));
+    }
+}
@@ -0,0 +1,852 @@
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::net::{Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr, TcpListener, TcpStream};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use bevy_app::{App, Plugin};
+use bevy_ecs::system::Resource;
+use crossbeam::channel::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::{error, info, warn};
+
+use crate::queue::ForwarderId;
+use crate::Queue;
+
+/// The largest frame a bridge will accept, to avoid allocating huge buffers
+/// when reading from a misbehaving peer.
+const MAX_FRAME_LEN: usize = 16 * 1024 * 1024;
+
+type BridgeFn = dyn Fn(&mut App, &mut BridgeRegistry) + Send + Sync;
+type ReceiveFn = Box<dyn Fn(&[u8]) + Send + Sync>;
+
+/// Where a [`QueueBridgePlugin`] listens for or connects to its peers.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BridgeEndpoint {
+    /// A TCP socket address, such as `"127.0.0.1:7777"`.
+    Tcp(String),
+    /// The path of a Unix domain socket.
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+/// Whether a [`QueueBridgePlugin`] accepts connections or makes one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BridgeRole {
+    /// Accept any number of peers at the endpoint, such as a server that
+    /// clients and spectators attach to.
+    Listen(BridgeEndpoint),
+    /// Connect to a single peer at the endpoint.
+    Connect(BridgeEndpoint),
+}
+
+/// Plugin that forwards [`Queue`] messages to another process over a local
+/// socket, and pushes the messages it receives into the matching queues.
+///
+/// Messages are serialized with bincode and sent as length-prefixed frames.
+/// Queues are identified by their type name, so both ends must be built from
+/// the same code.
+///
+/// Messages received from a peer are not sent back over the same bridge, so
+/// both ends can send and receive the same queue, but they are forwarded by
+/// the queue's other bridges and two-way event bridges. A listening bridge
+/// forwards to every connected peer, but does not relay messages between them.
+///
+/// The bridge's threads and connections are shut down when its state in
+/// [`QueueBridges`] is dropped, such as when the app is dropped.
+pub struct QueueBridgePlugin {
+    role: BridgeRole,
+    types: Vec<Box<BridgeFn>>,
+}
+
+impl QueueBridgePlugin {
+    /// Accept connections at the given TCP address.
+    pub fn listen_tcp(addr: impl Into<String>) -> Self {
+        Self::new(BridgeRole::Listen(BridgeEndpoint::Tcp(addr.into())))
+    }
+
+    /// Connect to the bridge listening at the given TCP address.
+    pub fn connect_tcp(addr: impl Into<String>) -> Self {
+        Self::new(BridgeRole::Connect(BridgeEndpoint::Tcp(addr.into())))
+    }
+
+    /// Accept connections at the Unix domain socket at the given path. The
+    /// path must not already exist.
+    #[cfg(unix)]
+    pub fn listen_unix(path: impl Into<PathBuf>) -> Self {
+        Self::new(BridgeRole::Listen(BridgeEndpoint::Unix(path.into())))
+    }
+
+    /// Connect to the bridge listening at the Unix domain socket at the given
+    /// path.
+    #[cfg(unix)]
+    pub fn connect_unix(path: impl Into<PathBuf>) -> Self {
+        Self::new(BridgeRole::Connect(BridgeEndpoint::Unix(path.into())))
+    }
+
+    /// Create a bridge with the given role.
+    pub fn new(role: BridgeRole) -> Self {
+        Self {
+            role,
+            types: Vec::new(),
+        }
+    }
+
+    /// Forward every item pushed onto the [`Queue`] of type `T` to the peers,
+    /// adding the queue to the app if needed. Several bridges can send the same
+    /// queue, and each forwards every item to its own peers.
+    pub fn send_queue<T>(mut self) -> Self
+    where
+        T: Serialize + Send + 'static,
+    {
+        self.types.push(Box::new(|app: &mut App, registry| {
+            app.init_resource::<Queue<T>>();
+            let peers = registry.peers.clone();
+            let name = std::any::type_name::<T>();
+            app.world.resource::<Queue<T>>().add_forwarder(
+                registry.forwarder_id(name),
+                Box::new(move |value: &T| match encode_frame(name, value) {
+                    Ok(frame) => peers.send(frame),
+                    Err(e) => error!("Failed to serialize bridged message: {:?}", e),
+                }),
+            );
+        }));
+        self
+    }
+
+    /// Push the items received from the peers onto the [`Queue`] of type `T`,
+    /// adding the queue to the app if needed.
+    pub fn receive_queue<T>(mut self) -> Self
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        self.types.push(Box::new(|app: &mut App, registry| {
+            app.init_resource::<Queue<T>>();
+            let sender = app.world.resource::<Queue<T>>().sender();
+            let name = std::any::type_name::<T>();
+            let source = registry.forwarder_id(name);
+            registry.receivers.insert(
+                name,
+                Box::new(move |bytes: &[u8]| match bincode::deserialize(bytes) {
+                    Ok(value) => sender.push_received(value, source),
+                    Err(e) => warn!("Skipping unreadable bridged message: {:?}", e),
+                }),
+            );
+        }));
+        self
+    }
+
+    /// Both send and receive the [`Queue`] of type `T`.
+    pub fn with_queue<T>(self) -> Self
+    where
+        T: Serialize + DeserializeOwned + Send + 'static,
+    {
+        self.send_queue::<T>().receive_queue::<T>()
+    }
+}
+
+impl Plugin for QueueBridgePlugin {
+    fn build(&self, app: &mut App) {
+        let (BridgeRole::Listen(endpoint) | BridgeRole::Connect(endpoint)) = &self.role;
+        let bridges = app.world.get_resource_or_insert_with(QueueBridges::default);
+        if bridges.0.contains_key(endpoint) {
+            error!("A bridge at {:?} has already been added", endpoint);
+            return;
+        }
+
+        let mut registry = BridgeRegistry::default();
+        for t in self.types.iter() {
+            t(app, &mut registry);
+        }
+        let peers = registry.peers.clone();
+        let receivers = Arc::new(registry.receivers);
+        let mut bridge = QueueBridge {
+            role: self.role.clone(),
+            local_addr: None,
+            acceptor: None,
+            peers: peers.clone(),
+        };
+        match &self.role {
+            BridgeRole::Listen(endpoint) => match Listener::bind(endpoint) {
+                Ok(listener) => {
+                    bridge.local_addr = listener.local_addr();
+                    let (stop, stopped) = channel::bounded(0);
+                    let thread = thread::spawn(move || accept(listener, peers, receivers, stopped));
+                    bridge.acceptor = Some(Acceptor {
+                        stop,
+                        thread,
+                        endpoint: endpoint.clone(),
+                        local_addr: bridge.local_addr,
+                    });
+                }
+                Err(e) => error!(
+                    "Failed to listen for bridge peers at {:?}: {:?}",
+                    endpoint, e
+                ),
+            },
+            BridgeRole::Connect(endpoint) => match Stream::connect(endpoint) {
+                Ok(stream) => {
+                    if let Err(e) = peers.add(stream, receivers) {
+                        error!("Failed to set up bridge connection: {:?}", e);
+                    }
+                }
+                Err(e) => error!("Failed to connect to bridge at {:?}: {:?}", endpoint, e),
+            },
+        }
+
+        // A TCP bridge listening on port 0 is keyed by the address it was
+        // given, so that several of them can be added.
+        let key = match (endpoint, bridge.local_addr) {
+            (BridgeEndpoint::Tcp(_), Some(addr)) => BridgeEndpoint::Tcp(addr.to_string()),
+            _ => endpoint.clone(),
+        };
+        app.world
+            .resource_mut::<QueueBridges>()
+            .0
+            .insert(key, bridge);
+    }
+
+    fn is_unique(&self) -> bool {
+        false
+    }
+}
+
+/// Resource holding the state of every [`QueueBridgePlugin`] in the app,
+/// keyed by endpoint.
+///
+/// A TCP bridge listening on port `0` is keyed by the address it is bound to,
+/// such as `BridgeEndpoint::Tcp("127.0.0.1:41234".into())`.
+#[derive(Resource, Default)]
+pub struct QueueBridges(HashMap<BridgeEndpoint, QueueBridge>);
+
+impl QueueBridges {
+    /// The bridge at the given endpoint.
+    pub fn get(&self, endpoint: &BridgeEndpoint) -> Option<&QueueBridge> {
+        self.0.get(endpoint)
+    }
+
+    /// Iterate over every bridge, with its endpoint.
+    pub fn iter(&self) -> impl Iterator<Item = (&BridgeEndpoint, &QueueBridge)> {
+        self.0.iter()
+    }
+
+    /// The number of bridges.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Test if there are no bridges.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// The state of a single [`QueueBridgePlugin`].
+///
+/// Dropping it stops accepting peers, disconnects every peer and waits for the
+/// bridge's threads to finish. A Unix domain socket the bridge was listening at
+/// is removed.
+pub struct QueueBridge {
+    role: BridgeRole,
+    local_addr: Option<SocketAddr>,
+    acceptor: Option<Acceptor>,
+    peers: Peers,
+}
+
+impl QueueBridge {
+    /// Whether the bridge listens for or connects to its peers.
+    pub fn role(&self) -> &BridgeRole {
+        &self.role
+    }
+
+    /// The address a TCP bridge is listening at. Useful when listening on port
+    /// `0` to let the OS pick a free port.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.local_addr
+    }
+
+    /// The number of peers currently connected.
+    pub fn peer_count(&self) -> usize {
+        self.peers.count()
+    }
+}
+
+impl Drop for QueueBridge {
+    fn drop(&mut self) {
+        if let Some(acceptor) = self.acceptor.take() {
+            acceptor.stop();
+        }
+        self.peers.shutdown();
+    }
+}
+
+/// The thread accepting peers for a listening bridge.
+struct Acceptor {
+    /// Dropped to tell the thread to stop.
+    stop: Sender<()>,
+    thread: JoinHandle<()>,
+    endpoint: BridgeEndpoint,
+    local_addr: Option<SocketAddr>,
+}
+
+impl Acceptor {
+    fn stop(self) {
+        drop(self.stop);
+        // Wake the thread from `accept` by connecting to it.
+        let woken = match (&self.endpoint, self.local_addr) {
+            (BridgeEndpoint::Tcp(_), Some(mut addr)) => {
+                if addr.ip().is_unspecified() {
+                    addr.set_ip(match addr {
+                        SocketAddr::V4(_) => Ipv4Addr::LOCALHOST.into(),
+                        SocketAddr::V6(_) => Ipv6Addr::LOCALHOST.into(),
+                    });
+                }
+                TcpStream::connect(addr).map(drop)
+            }
+            (BridgeEndpoint::Tcp(_), None) => Err(io::ErrorKind::AddrNotAvailable.into()),
+            #[cfg(unix)]
+            (BridgeEndpoint::Unix(path), _) => UnixStream::connect(path).map(drop),
+        };
+        match woken {
+            Ok(()) => {
+                if self.thread.join().is_err() {
+                    error!("Bridge accept thread panicked");
+                }
+            }
+            Err(e) => warn!("Failed to stop accepting bridge peers: {:?}", e),
+        }
+        #[cfg(unix)]
+        if let BridgeEndpoint::Unix(path) = &self.endpoint {
+            if let Err(e) = std::fs::remove_file(path) {
+                warn!("Failed to remove bridge socket at {:?}: {:?}", path, e);
+            }
+        }
+    }
+}
+
+/// Queues registered with a bridge while it is being built.
+#[derive(Default)]
+struct BridgeRegistry {
+    peers: Peers,
+    receivers: HashMap<&'static str, ReceiveFn>,
+    forwarders: HashMap<&'static str, ForwarderId>,
+}
+
+impl BridgeRegistry {
+    /// The id of the bridge's forwarder for the queue with the given name, so
+    /// that messages received for the queue aren't sent back to the peers.
+    fn forwarder_id(&mut self, name: &'static str) -> ForwarderId {
+        *self.forwarders.entry(name).or_insert_with(ForwarderId::new)
+    }
+}
+
+/// The connected peers of a bridge, and the threads reading from and writing
+/// to them.
+#[derive(Clone, Default)]
+struct Peers(Arc<PeersInner>);
+
+#[derive(Default)]
+struct PeersInner {
+    peers: Mutex<Vec<Peer>>,
+    threads: Mutex<Vec<JoinHandle<()>>>,
+}
+
+/// A connected peer. Outgoing frames are sent to its channel, which is drained
+/// by a writer thread.
+struct Peer {
+    frames: Sender<Arc<[u8]>>,
+    connected: Arc<AtomicBool>,
+    stream: Stream,
+}
+
+impl Peers {
+    fn count(&self) -> usize {
+        let mut peers = self.0.peers.lock().unwrap();
+        peers.retain(|peer| peer.connected.load(Ordering::Relaxed));
+        peers.len()
+    }
+
+    fn send(&self, frame: Arc<[u8]>) {
+        self.0.peers.lock().unwrap().retain(|peer| {
+            peer.connected.load(Ordering::Relaxed) && peer.frames.send(frame.clone()).is_ok()
+        });
+    }
+
+    /// Disconnect every peer and wait for their threads to finish.
+    fn shutdown(&self) {
+        for peer in std::mem::take(&mut *self.0.peers.lock().unwrap()) {
+            // Unblocks the reader and writer threads if they are waiting on
+            // the peer.
+            let _ = peer.stream.shutdown();
+        }
+        for thread in std::mem::take(&mut *self.0.threads.lock().unwrap()) {
+            if thread.join().is_err() {
+                error!("Bridge peer thread panicked");
+            }
+        }
+    }
+
+    /// Start the reader and writer threads for a newly connected peer.
+    fn add(
+        &self,
+        stream: Stream,
+        receivers: Arc<HashMap<&'static str, ReceiveFn>>,
+    ) -> io::Result<()> {
+        let mut writer = stream.try_clone()?;
+        let (sender, frames) = channel::unbounded::<Arc<[u8]>>();
+        let connected = Arc::new(AtomicBool::new(true));
+        // Register the peer before reading from it, so that anything pushed in
+        // response to its messages is sent back to it.
+        self.0.peers.lock().unwrap().push(Peer {
+            frames: sender,
+            connected: connected.clone(),
+            stream: stream.try_clone()?,
+        });
+        let mut threads = self.0.threads.lock().unwrap();
+        threads.retain(|thread| !thread.is_finished());
+        let writer_connected = connected.clone();
+        threads.push(thread::spawn(move || {
+            for frame in frames {
+                if let Err(e) = writer.write_all(&frame) {
+                    info!("Bridge peer disconnected: {:?}", e);
+                    break;
+                }
+            }
+            writer_connected.store(false, Ordering::Relaxed);
+        }));
+        let reader_connected = connected.clone();
+        threads.push(thread::spawn(move || {
+            read_frames(stream, &receivers);
+            reader_connected.store(false, Ordering::Relaxed);
+        }));
+        Ok(())
+    }
+}
+
+/// Encode a message as a frame: the length of the rest of the frame, the
+/// length of the queue name, the queue name, and the bincode-encoded message.
+fn encode_frame<T: Serialize>(name: &str, value: &T) -> bincode::Result<Arc<[u8]>> {
+    if name.len() > u16::MAX as usize {
+        return Err(Box::new(bincode::ErrorKind::Custom(format!(
+            "queue name `{name}` is too long to bridge"
+        ))));
+    }
+    let message = bincode::serialize(value)?;
+    let len = 2 + name.len() + message.len();
+    if len > MAX_FRAME_LEN {
+        return Err(Box::new(bincode::ErrorKind::Custom(format!(
+            "message of {len} bytes is too large to bridge"
+        ))));
+    }
+    let mut frame = Vec::with_capacity(4 + len);
+    frame.extend_from_slice(&(len as u32).to_le_bytes());
+    frame.extend_from_slice(&(name.len() as u16).to_le_bytes());
+    frame.extend_from_slice(name.as_bytes());
+    frame.extend_from_slice(&message);
+    Ok(frame.into())
+}
+
+/// Read frames from a peer until it disconnects, passing each message to the
+/// receiver of its queue.
+fn read_frames(mut stream: Stream, receivers: &HashMap<&'static str, ReceiveFn>) {
+    let mut buf = Vec::new();
+    loop {
+        let mut len = [0; 4];
+        if let Err(e) = stream.read_exact(&mut len) {
+            info!("Bridge peer disconnected: {:?}", e);
+            return;
+        }
+        let len = u32::from_le_bytes(len) as usize;
+        if !(2..=MAX_FRAME_LEN).contains(&len) {
+            error!(
+                "Received invalid bridge frame of length {}, disconnecting",
+                len
+            );
+            return;
+        }
+        buf.resize(len, 0);
+        if let Err(e) = stream.read_exact(&mut buf) {
+            info!("Bridge peer disconnected: {:?}", e);
+            return;
+        }
+        let name_len = u16::from_le_bytes([buf[0], buf[1]]) as usize;
+        let Some(name) = buf.get(2..2 + name_len) else {
+            error!("Received invalid bridge frame, disconnecting");
+            return;
+        };
+        let name = String::from_utf8_lossy(name);
+        match receivers.get(name.as_ref()) {
+            Some(receive) => receive(&buf[2 + name_len..]),
+            None => warn!("Skipping bridged message for unknown queue `{}`", name),
+        }
+    }
+}
+
+/// Accept peers until `stopped` is disconnected, backing off while accepting
+/// fails, such as when the process has run out of file descriptors.
+fn accept(
+    listener: Listener,
+    peers: Peers,
+    receivers: Arc<HashMap<&'static str, ReceiveFn>>,
+    stopped: Receiver<()>,
+) {
+    const MIN_BACKOFF: Duration = Duration::from_millis(10);
+    const MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+    let mut backoff = MIN_BACKOFF;
+    loop {
+        let accepted = listener.accept();
+        if let Err(TryRecvError::Disconnected) = stopped.try_recv() {
+            return;
+        }
+        match accepted {
+            Ok(stream) => {
+                backoff = MIN_BACKOFF;
+                if let Err(e) = peers.add(stream, receivers.clone()) {
+                    error!("Failed to set up bridge connection: {:?}", e);
+                }
+            }
+            Err(e) => {
+                error!(
+                    "Failed to accept bridge peer, retrying in {:?}: {:?}",
+                    backoff, e
+                );
+                if let Err(RecvTimeoutError::Disconnected) = stopped.recv_timeout(backoff) {
+                    return;
+                }
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        }
+    }
+}
+
+enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener),
+}
+
+impl Listener {
+    fn bind(endpoint: &BridgeEndpoint) -> io::Result<Self> {
+        match endpoint {
+            BridgeEndpoint::Tcp(addr) => TcpListener::bind(addr).map(Listener::Tcp),
+            #[cfg(unix)]
+            BridgeEndpoint::Unix(path) => UnixListener::bind(path).map(Listener::Unix),
+        }
+    }
+
+    fn local_addr(&self) -> Option<SocketAddr> {
+        match self {
+            Listener::Tcp(listener) => listener.local_addr().ok(),
+            #[cfg(unix)]
+            Listener::Unix(_) => None,
+        }
+    }
+
+    fn accept(&self) -> io::Result<Stream> {
+        match self {
+            Listener::Tcp(listener) => {
+                let (stream, _) = listener.accept()?;
+                stream.set_nodelay(true)?;
+                Ok(Stream::Tcp(stream))
+            }
+            #[cfg(unix)]
+            Listener::Unix(listener) => listener.accept().map(|(stream, _)| Stream::Unix(stream)),
+        }
+    }
+}
+
+enum Stream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+impl Stream {
+    fn connect(endpoint: &BridgeEndpoint) -> io::Result<Self> {
+        match endpoint {
+            BridgeEndpoint::Tcp(addr) => {
+                let stream = TcpStream::connect(addr)?;
+                stream.set_nodelay(true)?;
+                Ok(Stream::Tcp(stream))
+            }
+            #[cfg(unix)]
+            BridgeEndpoint::Unix(path) => UnixStream::connect(path).map(Stream::Unix),
+        }
+    }
+
+    fn try_clone(&self) -> io::Result<Self> {
+        match self {
+            Stream::Tcp(stream) => stream.try_clone().map(Stream::Tcp),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.try_clone().map(Stream::Unix),
+        }
+    }
+
+    fn shutdown(&self) -> io::Result<()> {
+        match self {
+            Stream::Tcp(stream) => stream.shutdown(Shutdown::Both),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.shutdown(Shutdown::Both),
+        }
+    }
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Stream::Tcp(stream) => stream.read(buf),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Stream::Tcp(stream) => stream.write(buf),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Stream::Tcp(stream) => stream.flush(),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.flush(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use bevy_app::{First, Last, Update};
+    use bevy_ecs::prelude::*;
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::{EventBridgeDirection, QueuePlugin};
+
+    #[derive(Event, Serialize, Deserialize, Clone, Debug, PartialEq)]
+    struct Ping(u32);
+
+    /// Wait until `done` returns true, failing after a few seconds.
+    fn wait_for(mut done: impl FnMut() -> bool) {
+        let timeout = Instant::now() + Duration::from_secs(5);
+        while !done() {
+            assert!(Instant::now() < timeout, "timed out");
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    /// Add a listening bridge, returning the address it is bound to.
+    fn listen(app: &mut App, plugin: QueueBridgePlugin) -> SocketAddr {
+        let addrs = |app: &App| -> Vec<SocketAddr> {
+            app.world
+                .get_resource::<QueueBridges>()
+                .into_iter()
+                .flat_map(|bridges| bridges.iter())
+                .filter_map(|(_, bridge)| bridge.local_addr())
+                .collect()
+        };
+        let before = addrs(app);
+        app.add_plugins(plugin);
+        addrs(app)
+            .into_iter()
+            .find(|addr| !before.contains(addr))
+            .unwrap()
+    }
+
+    fn bridge(app: &App, endpoint: BridgeEndpoint) -> &QueueBridge {
+        app.world.resource::<QueueBridges>().get(&endpoint).unwrap()
+    }
+
+    fn connect(addr: SocketAddr) -> App {
+        let mut app = App::new();
+        app.add_plugins(QueueBridgePlugin::connect_tcp(addr.to_string()).with_queue::<Ping>());
+        app
+    }
+
+    #[test]
+    fn items_cross_once_and_are_not_sent_back() {
+        let mut server = App::new();
+        let addr = listen(
+            &mut server,
+            QueueBridgePlugin::listen_tcp("127.0.0.1:0").with_queue::<Ping>(),
+        );
+        let client = connect(addr);
+        let endpoint = BridgeEndpoint::Tcp(addr.to_string());
+        wait_for(|| bridge(&server, endpoint.clone()).peer_count() == 1);
+
+        client.world.resource::<Queue<Ping>>().push(Ping(1));
+        server.world.resource::<Queue<Ping>>().push(Ping(2));
+
+        let server_queue = server.world.resource::<Queue<Ping>>();
+        let client_queue = client.world.resource::<Queue<Ping>>();
+        wait_for(|| server_queue.len() == 2 && client_queue.len() == 2);
+        // Give any echoes time to arrive before checking for them.
+        thread::sleep(Duration::from_millis(100));
+        for queue in [server_queue, client_queue] {
+            let mut received: Vec<_> = queue.iter().map(|Ping(i)| i).collect();
+            received.sort_unstable();
+            assert_eq!(received, [1, 2]);
+        }
+    }
+
+    #[test]
+    fn several_bridges_send_the_same_queue() {
+        let mut server = App::new();
+        let first = listen(
+            &mut server,
+            QueueBridgePlugin::listen_tcp("127.0.0.1:0").with_queue::<Ping>(),
+        );
+        let second = listen(
+            &mut server,
+            QueueBridgePlugin::listen_tcp("127.0.0.1:0").with_queue::<Ping>(),
+        );
+        let clients = [connect(first), connect(second)];
+        assert_eq!(server.world.resource::<QueueBridges>().len(), 2);
+
+        // Messages received by one bridge are sent on by the other, and once a
+        // client's message has arrived, its bridge has accepted it.
+        for (i, client) in clients.iter().enumerate() {
+            client
+                .world
+                .resource::<Queue<Ping>>()
+                .push(Ping(10 + i as u32));
+        }
+        let server_queue = server.world.resource::<Queue<Ping>>();
+        wait_for(|| server_queue.len() == 2);
+        server_queue.iter().for_each(drop);
+
+        server_queue.push(Ping(1));
+        for client in &clients {
+            let queue = client.world.resource::<Queue<Ping>>();
+            wait_for(|| queue.len() == 3);
+            let mut received: Vec<_> = queue.iter().map(|Ping(i)| i).collect();
+            received.sort_unstable();
+            assert_eq!(received, [1, 10, 11]);
+        }
+    }
+
+    #[test]
+    fn items_cross_between_peers_and_events() {
+        #[derive(Resource, Default)]
+        struct Seen(Vec<u32>);
+
+        let mut server = App::new();
+        server
+            .add_plugins(
+                QueuePlugin::default().bridge_events::<Ping>(EventBridgeDirection::Both, Update),
+            )
+            .init_resource::<Seen>()
+            .add_systems(
+                First,
+                |mut events: EventWriter<Ping>, mut sent: Local<bool>| {
+                    if !std::mem::replace(&mut *sent, true) {
+                        events.send(Ping(2));
+                    }
+                },
+            )
+            .add_systems(
+                Last,
+                |mut events: EventReader<Ping>, mut seen: ResMut<Seen>| {
+                    seen.0.extend(events.read().map(|Ping(i)| i));
+                },
+            );
+        let addr = listen(
+            &mut server,
+            QueueBridgePlugin::listen_tcp("127.0.0.1:0").with_queue::<Ping>(),
+        );
+        let client = connect(addr);
+        let endpoint = BridgeEndpoint::Tcp(addr.to_string());
+        wait_for(|| bridge(&server, endpoint.clone()).peer_count() == 1);
+
+        // A message from the peer is sent as an event, and an event is sent to
+        // the peer, but neither comes back the way it came.
+        client.world.resource::<Queue<Ping>>().push(Ping(1));
+        wait_for(|| server.world.resource::<Queue<Ping>>().len() == 1);
+        for _ in 0..4 {
+            server.update();
+        }
+        let client_queue = client.world.resource::<Queue<Ping>>();
+        wait_for(|| client_queue.len() == 2);
+        thread::sleep(Duration::from_millis(100));
+
+        let mut seen = server.world.resource::<Seen>().0.clone();
+        seen.sort_unstable();
+        assert_eq!(seen, [1, 2]);
+        assert_eq!(client_queue.iter().collect::<Vec<_>>(), [Ping(1), Ping(2)]);
+        let mut queued: Vec<_> = server.world.resource::<Queue<Ping>>().iter().collect();
+        queued.sort_unstable_by_key(|Ping(i)| *i);
+        assert_eq!(queued, [Ping(1), Ping(2)]);
+    }
+
+    #[test]
+    fn dropping_the_app_disconnects_peers() {
+        let mut server = App::new();
+        let addr = listen(
+            &mut server,
+            QueueBridgePlugin::listen_tcp("127.0.0.1:0").with_queue::<Ping>(),
+        );
+        let client = connect(addr);
+        let endpoint = BridgeEndpoint::Tcp(addr.to_string());
+        wait_for(|| bridge(&client, endpoint.clone()).peer_count() == 1);
+
+        drop(server);
+        wait_for(|| bridge(&client, endpoint.clone()).peer_count() == 0);
+        assert!(TcpStream::connect(addr).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_bridge_removes_its_socket() {
+        let path =
+            std::env::temp_dir().join(format!("rouge_queue_bridge_{}.sock", std::process::id()));
+        let mut server = App::new();
+        server.add_plugins(QueueBridgePlugin::listen_unix(&path).with_queue::<Ping>());
+        let mut client = App::new();
+        client.add_plugins(QueueBridgePlugin::connect_unix(&path).with_queue::<Ping>());
+
+        client.world.resource::<Queue<Ping>>().push(Ping(1));
+        let queue = server.world.resource::<Queue<Ping>>();
+        wait_for(|| queue.len() == 1);
+        assert_eq!(queue.pop(), Some(Ping(1)));
+
+        drop(server);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn rejects_a_second_bridge_at_an_endpoint() {
+        let mut app = App::new();
+        let addr = listen(
+            &mut app,
+            QueueBridgePlugin::listen_tcp("127.0.0.1:0").with_queue::<Ping>(),
+        );
+        app.add_plugins(QueueBridgePlugin::connect_tcp(addr.to_string()).with_queue::<Ping>());
+        let endpoint = BridgeEndpoint::Tcp(addr.to_string());
+        assert_eq!(app.world.resource::<QueueBridges>().len(), 1);
+        assert_eq!(
+            bridge(&app, endpoint).role(),
+            &BridgeRole::Listen(BridgeEndpoint::Tcp("127.0.0.1:0".into()))
+        );
+    }
+
+    #[test]
+    fn rejects_oversized_names() {
+        let name = "a".repeat(u16::MAX as usize + 1);
+        assert!(encode_frame(&name, &Ping(1)).is_err());
+        assert!(encode_frame("Ping", &Ping(1)).is_ok());
+    }
+}
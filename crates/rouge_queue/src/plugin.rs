@@ -1,8 +1,21 @@
-use bevy::{app::Plugin, prelude::World};
+use std::any::TypeId;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
 
-use crate::Queue;
+use bevy_app::{App, First, FixedUpdate, Last, Plugin, PostUpdate, PreUpdate, Update};
+use bevy_ecs::prelude::*;
+use bevy_ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy_time::Time;
 
-type QueueFn = dyn Fn(&mut bevy::app::App) + Send + Sync;
+use crate::diagnostics::add_queue_diagnostics;
+use crate::{
+    apply_world_commands, events_to_queue, forward_events_to_queue, forward_queue_to_events,
+    queue_to_events, remove_despawned_actors, AddQueue, BroadcastQueue, CoalescingQueue,
+    EventBridgeDirection, OverflowPolicy, PriorityQueue, Queue, QueueSet, Responder, TurnScheduler,
+    TwoWayBridge, WorldCommand,
+};
+
+type QueueFn = dyn Fn(&mut App) + Send + Sync;
 
 /// Plugin that automatically adds [`Queue`] resources to the app with the types
 /// provided to the [`QueuePlugin::with_queue`] builder.
@@ -16,16 +29,202 @@ pub struct QueuePlugin {
 
 impl QueuePlugin {
     /// Add a [`Queue`] of type `T` to the application.
+    ///
+    /// Items pushed with [`Queue::push_delayed`] using a time delay are
+    /// released as Bevy's `Time` advances. The queue's traffic is registered
+    /// with Bevy's diagnostics under its type name, see
+    /// [`QueueDiagnosticPaths`](crate::QueueDiagnosticPaths).
+    ///
+    /// [`QueueSet::Write`] is ordered before [`QueueSet::Read`] for the queue
+    /// in each of the main schedules.
     pub fn with_queue<T: Send + 'static>(mut self) -> Self {
-        self.types.push(Box::new(|app: &mut bevy::app::App| {
-            app.init_resource::<Queue<T>>();
+        self.types.push(Box::new(init_queue::<T>));
+        self
+    }
+
+    /// Add a [`Queue`] of type `T` and Bevy `Events` of the same type to the
+    /// application, and forward items between them in the given schedule.
+    ///
+    /// With [`EventBridgeDirection::QueueToEvents`], the queue is drained in
+    /// its [`QueueSet::Read`] set and its items are sent as events. With
+    /// [`EventBridgeDirection::EventsToQueue`], events are pushed onto the
+    /// queue in its [`QueueSet::Write`] set. With
+    /// [`EventBridgeDirection::Both`], items pushed onto the queue are also
+    /// sent as events in the `Read` set, and events not sent by the bridge are
+    /// pushed onto the queue in the `Write` set.
+    ///
+    /// Only the first bridge for each type is added.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the type is already bridged in a different direction. Use
+    /// [`EventBridgeDirection::Both`] to bridge both ways.
+    pub fn bridge_events<T: Event + Clone>(
+        mut self,
+        direction: EventBridgeDirection,
+        schedule: impl ScheduleLabel + Clone,
+    ) -> Self {
+        self.types.push(Box::new(move |app: &mut App| {
+            init_queue::<T>(app);
+            app.add_event::<T>();
+            let previous = app
+                .world
+                .get_resource_or_insert_with(EventBridges::default)
+                .0
+                .insert(TypeId::of::<T>(), direction);
+            match previous {
+                Some(previous) if previous != direction => panic!(
+                    "`{}` is already bridged with {:?}, and can't also be bridged with {:?}; use `EventBridgeDirection::Both` to bridge both ways",
+                    std::any::type_name::<T>(),
+                    previous,
+                    direction
+                ),
+                Some(_) => return,
+                None => {}
+            }
+            match direction {
+                EventBridgeDirection::QueueToEvents => app.add_systems(
+                    schedule.clone(),
+                    queue_to_events::<T>.in_set(QueueSet::<T>::Read),
+                ),
+                EventBridgeDirection::EventsToQueue => app.add_systems(
+                    schedule.clone(),
+                    events_to_queue::<T>.in_set(QueueSet::<T>::Write),
+                ),
+                EventBridgeDirection::Both => {
+                    let bridge = TwoWayBridge::new(app.world.resource::<Queue<T>>());
+                    app.insert_resource(bridge).add_systems(
+                        schedule.clone(),
+                        (
+                            forward_events_to_queue::<T>.in_set(QueueSet::<T>::Write),
+                            forward_queue_to_events::<T>.in_set(QueueSet::<T>::Read),
+                        ),
+                    )
+                }
+            };
+        }));
+        self
+    }
+
+    /// Add a [`RequestQueue`](crate::RequestQueue) of requests of type `Req`
+    /// answered with responses of type `R` to the application.
+    pub fn with_request_queue<Req: Send + 'static, R: Send + 'static>(self) -> Self {
+        self.with_queue::<(Req, Responder<R>)>()
+    }
+
+    /// Add a [`BoundedQueue`] of type `T` to the application, holding at most
+    /// `capacity` items.
+    pub fn with_bounded_queue<T: Send + 'static>(
+        mut self,
+        capacity: usize,
+        policy: OverflowPolicy,
+    ) -> Self {
+        self.types.push(Box::new(move |app: &mut App| {
+            app.add_bounded_queue::<T>(capacity, policy);
+        }));
+        self
+    }
+
+    /// Add a [`Queue`] of [`WorldCommand`]s to the application, and apply them
+    /// to the `World` in the given schedule, in its [`QueueSet::Read`] set.
+    pub fn with_world_commands(mut self, schedule: impl ScheduleLabel + Clone) -> Self {
+        self.types.push(Box::new(move |app: &mut App| {
+            app.init_resource::<Queue<WorldCommand>>();
+            if first_registration::<WorldCommand>(app) {
+                configure_queue_sets::<WorldCommand>(app);
+                app.add_systems(
+                    schedule.clone(),
+                    apply_world_commands.in_set(QueueSet::<WorldCommand>::Read),
+                );
+            }
+        }));
+        self
+    }
+
+    /// Add a [`CoalescingQueue`] of items of type `T` keyed by `K` to the
+    /// application.
+    pub fn with_coalescing_queue<K, T>(mut self) -> Self
+    where
+        K: Eq + Hash + Clone + Send + 'static,
+        T: Send + 'static,
+    {
+        self.types.push(Box::new(|app: &mut App| {
+            app.add_coalescing_queue::<K, T>();
+        }));
+        self
+    }
+
+    /// Add a [`PriorityQueue`] of type `T` to the application.
+    pub fn with_priority_queue<T: Send + 'static>(mut self) -> Self {
+        self.types.push(Box::new(|app: &mut App| {
+            app.add_priority_queue::<T>();
+        }));
+        self
+    }
+
+    /// Add a [`BroadcastQueue`] of type `T` to the application.
+    pub fn with_broadcast_queue<T: Send + 'static>(mut self) -> Self {
+        self.types.push(Box::new(|app: &mut App| {
+            app.add_broadcast_queue::<T>();
         }));
         self
     }
 }
 
+/// Types of the queues whose systems have been added by a `QueuePlugin`, so
+/// that adding the same queue from several plugins doesn't duplicate them.
+#[derive(Resource, Default)]
+struct RegisteredQueues(HashSet<TypeId>);
+
+/// Directions of the event bridges added by a `QueuePlugin`, by type.
+#[derive(Resource, Default)]
+struct EventBridges(HashMap<TypeId, EventBridgeDirection>);
+
+/// Returns `true` the first time it is called with the type `Q` for this app.
+fn first_registration<Q: 'static>(app: &mut App) -> bool {
+    app.world
+        .get_resource_or_insert_with(RegisteredQueues::default)
+        .0
+        .insert(TypeId::of::<Q>())
+}
+
+/// Add a [`Queue`] of type `T`, and its systems if it hasn't been added by a
+/// `QueuePlugin` yet.
+fn init_queue<T: Send + 'static>(app: &mut App) {
+    app.init_resource::<Queue<T>>();
+    if first_registration::<Queue<T>>(app) {
+        configure_queue_sets::<T>(app);
+        app.add_systems(
+            PreUpdate,
+            advance_queue_time::<T>.in_set(QueueSet::<T>::Write),
+        );
+        add_queue_diagnostics::<T>(app);
+    }
+}
+
+/// Order the [`QueueSet`]s of the queue of type `T` in the main schedules.
+fn configure_queue_sets<T: Send + 'static>(app: &mut App) {
+    let schedules: [InternedScheduleLabel; 6] = [
+        First.intern(),
+        PreUpdate.intern(),
+        FixedUpdate.intern(),
+        Update.intern(),
+        PostUpdate.intern(),
+        Last.intern(),
+    ];
+    for schedule in schedules {
+        app.configure_sets(schedule, QueueSet::<T>::Write.before(QueueSet::<T>::Read));
+    }
+}
+
+fn advance_queue_time<T: Send + 'static>(queue: Res<Queue<T>>, time: Option<Res<Time>>) {
+    if let Some(time) = time {
+        queue.advance_time(time.delta());
+    }
+}
+
 impl Plugin for QueuePlugin {
-    fn build(&self, app: &mut bevy::app::App) {
+    fn build(&self, app: &mut App) {
         for t in self.types.iter() {
             t(app);
         }
@@ -36,20 +235,40 @@ impl Plugin for QueuePlugin {
     }
 }
 
-/// Trait for types that can add a [`Queue`] resource.
-pub trait AddQueue {
-    /// Add a [`Queue`] of type `T` to the `World`.
-    fn add_queue<T: Send + 'static>(&mut self);
-}
+/// Plugin that adds a [`TurnScheduler`] resource to the app, and removes
+/// entities from it when they are despawned.
+#[derive(Default)]
+pub struct TurnSchedulerPlugin;
 
-impl AddQueue for World {
-    fn add_queue<T: Send + 'static>(&mut self) {
-        self.init_resource::<Queue<T>>();
+impl Plugin for TurnSchedulerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TurnScheduler>()
+            .add_systems(First, remove_despawned_actors);
     }
 }
 
-impl AddQueue for bevy::prelude::App {
+impl AddQueue for App {
     fn add_queue<T: Send + 'static>(&mut self) {
         self.init_resource::<Queue<T>>();
     }
+
+    fn add_bounded_queue<T: Send + 'static>(&mut self, capacity: usize, policy: OverflowPolicy) {
+        self.world.add_bounded_queue::<T>(capacity, policy);
+    }
+
+    fn add_coalescing_queue<K, T>(&mut self)
+    where
+        K: Eq + Hash + Clone + Send + 'static,
+        T: Send + 'static,
+    {
+        self.init_resource::<CoalescingQueue<K, T>>();
+    }
+
+    fn add_priority_queue<T: Send + 'static>(&mut self) {
+        self.init_resource::<PriorityQueue<T>>();
+    }
+
+    fn add_broadcast_queue<T: Send + 'static>(&mut self) {
+        self.init_resource::<BroadcastQueue<T>>();
+    }
 }
@@ -0,0 +1,237 @@
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+
+use bevy_ecs::{prelude::*, system::SystemParam};
+use crossbeam::queue::SegQueue;
+
+/// The lane of a [`PriorityQueue`] an item is pushed to.
+///
+/// Priorities compare by urgency, so `Priority::High > Priority::Low`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Priority {
+    /// Urgent items, such as cancelling or interrupting an action.
+    High,
+    /// Items with no particular urgency.
+    #[default]
+    Normal,
+    /// Bulk items that can wait, such as the steps of an automatic action.
+    Low,
+}
+
+impl Priority {
+    /// Every priority, from highest to lowest.
+    pub const ALL: [Priority; 3] = [Priority::High, Priority::Normal, Priority::Low];
+
+    fn lane(self) -> usize {
+        self as usize
+    }
+}
+
+impl Ord for Priority {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Lanes are numbered from the highest priority down.
+        other.lane().cmp(&self.lane())
+    }
+}
+
+impl PartialOrd for Priority {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A multi-producer multi-consumer queue with a lane for each [`Priority`].
+///
+/// Readers always get items from higher lanes first, and items in the same lane
+/// in the order they were pushed.
+#[derive(Resource)]
+pub struct PriorityQueue<T> {
+    lanes: [SegQueue<T>; 3],
+}
+
+impl<T> Default for PriorityQueue<T> {
+    fn default() -> Self {
+        Self {
+            lanes: Default::default(),
+        }
+    }
+}
+
+impl<T> PriorityQueue<T> {
+    /// Create a new, empty queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of items in every lane of the queue.
+    pub fn len(&self) -> usize {
+        self.lanes.iter().map(SegQueue::len).sum()
+    }
+
+    /// The number of items in the given lane of the queue.
+    pub fn lane_len(&self, priority: Priority) -> usize {
+        self.lanes[priority.lane()].len()
+    }
+
+    /// Test if every lane of the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.lanes.iter().all(SegQueue::is_empty)
+    }
+
+    /// Push an item onto the [`Priority::Normal`] lane.
+    pub fn push(&self, value: T) {
+        self.push_with_priority(value, Priority::Normal)
+    }
+
+    /// Push an item onto the lane of the given priority.
+    pub fn push_with_priority(&self, value: T, priority: Priority) {
+        self.lanes[priority.lane()].push(value)
+    }
+
+    /// Pop the first item off of the highest non-empty lane. Returns `None` if
+    /// the queue is empty.
+    pub fn pop(&self) -> Option<T> {
+        self.lanes.iter().find_map(SegQueue::pop)
+    }
+
+    /// Pop the first item off of the lane of the given priority, ignoring the
+    /// other lanes.
+    pub fn pop_lane(&self, priority: Priority) -> Option<T> {
+        self.lanes[priority.lane()].pop()
+    }
+
+    /// Iterate over items in the queue, highest lanes first. This drains the
+    /// queue, but does not consume the `PriorityQueue` itself.
+    ///
+    /// Items pushed onto a higher lane while iterating are returned before the
+    /// rest of the lower lanes.
+    pub fn iter(&self) -> PriorityQueueIter<'_, T> {
+        PriorityQueueIter { q: self }
+    }
+}
+
+/// An iterator for `PriorityQueue`.
+///
+/// Created by calling [`PriorityQueue::iter`]. See its documentation for more.
+pub struct PriorityQueueIter<'a, T> {
+    q: &'a PriorityQueue<T>,
+}
+
+impl<'a, T> Iterator for PriorityQueueIter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.q.pop()
+    }
+}
+
+/// Push items onto a `PriorityQueue`.
+///
+/// Used as a convenience `SystemParam` rather than operating on the underlying
+/// [`PriorityQueue`] directly.
+#[derive(SystemParam)]
+pub struct PriorityQueueWriter<'w, 's, E>
+where
+    E: Send + 'static,
+{
+    queue: Res<'w, PriorityQueue<E>>,
+    #[system_param(ignore)]
+    marker: PhantomData<&'s ()>,
+}
+
+impl<'w, 's, E> PriorityQueueWriter<'w, 's, E>
+where
+    E: Send + 'static,
+{
+    /// Push an item onto the [`Priority::Normal`] lane.
+    pub fn push(&self, value: E) {
+        self.queue.push(value)
+    }
+
+    /// Push an item onto the lane of the given priority.
+    pub fn push_with_priority(&self, value: E, priority: Priority) {
+        self.queue.push_with_priority(value, priority)
+    }
+}
+
+/// Pop items from a `PriorityQueue`.
+///
+/// Used as a convenience `SystemParam` rather than operating on the underlying
+/// [`PriorityQueue`] directly.
+#[derive(SystemParam)]
+pub struct PriorityQueueReader<'w, 's, E>
+where
+    E: Send + 'static,
+{
+    queue: Res<'w, PriorityQueue<E>>,
+    #[system_param(ignore)]
+    marker: PhantomData<&'s ()>,
+}
+
+impl<'w, 's, E> PriorityQueueReader<'w, 's, E>
+where
+    E: Send + 'static,
+{
+    /// Check if the queue is empty without modifying the contents of the
+    /// `PriorityQueue`.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Pop the first item off of the highest non-empty lane.
+    pub fn pop(&self) -> Option<E> {
+        self.queue.pop()
+    }
+
+    /// Iterate over through items in the `PriorityQueue`, highest lanes first.
+    /// This drains the `queue`, but does not consume the underlying
+    /// `PriorityQueue`.
+    pub fn iter(&self) -> PriorityQueueIter<'_, E> {
+        self.queue.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn priorities_compare_by_urgency() {
+        assert!(Priority::High > Priority::Normal);
+        assert!(Priority::Normal > Priority::Low);
+        assert_eq!(Priority::ALL.iter().max(), Some(&Priority::High));
+
+        let mut sorted = [Priority::Normal, Priority::High, Priority::Low];
+        sorted.sort_unstable_by(|a, b| b.cmp(a));
+        assert_eq!(sorted, Priority::ALL);
+    }
+
+    #[test]
+    fn high_items_drain_first_in_push_order() {
+        let queue = PriorityQueue::new();
+        queue.push_with_priority("low 1", Priority::Low);
+        queue.push("normal 1");
+        queue.push_with_priority("high 1", Priority::High);
+        queue.push_with_priority("low 2", Priority::Low);
+        queue.push_with_priority("high 2", Priority::High);
+        queue.push("normal 2");
+
+        assert_eq!(queue.lane_len(Priority::High), 2);
+        assert_eq!(
+            queue.iter().collect::<Vec<_>>(),
+            ["high 1", "high 2", "normal 1", "normal 2", "low 1", "low 2"]
+        );
+    }
+
+    #[test]
+    fn pop_lane_ignores_other_lanes() {
+        let queue = PriorityQueue::new();
+        queue.push_with_priority(1, Priority::High);
+        queue.push_with_priority(2, Priority::Low);
+
+        assert_eq!(queue.pop_lane(Priority::Normal), None);
+        assert_eq!(queue.pop_lane(Priority::Low), Some(2));
+        assert_eq!(queue.pop(), Some(1));
+        assert!(queue.is_empty());
+    }
+}
@@ -1,18 +1,352 @@
+use std::collections::BTreeMap;
+use std::future::Future;
 use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::atomic::{fence, AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
 
-use bevy::ecs::{prelude::*, system::SystemParam};
+#[cfg(feature = "trace")]
+use bevy_ecs::system::SystemName;
+use bevy_ecs::{prelude::*, system::SystemParam};
 use crossbeam::queue::SegQueue;
+use futures_core::Stream;
+#[cfg(feature = "trace")]
+use tracing::{trace, Span};
+
+#[cfg(feature = "trace")]
+use crate::Traced;
+
+/// How long to hold back an item pushed with [`Queue::push_delayed`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Delay {
+    /// Delay by a number of ticks, advanced with [`Queue::tick`].
+    Ticks(u64),
+    /// Delay by an amount of time, advanced with [`Queue::advance_time`].
+    Time(Duration),
+}
+
+impl From<Duration> for Delay {
+    fn from(duration: Duration) -> Self {
+        Delay::Time(duration)
+    }
+}
+
+/// Items pushed with a delay, keyed by when they are due and then by the order
+/// they were pushed in.
+struct Delayed<T> {
+    ticks: u64,
+    elapsed: Duration,
+    next_seq: u64,
+    by_ticks: BTreeMap<(u64, u64), T>,
+    by_time: BTreeMap<(Duration, u64), T>,
+}
+
+impl<T> Default for Delayed<T> {
+    fn default() -> Self {
+        Self {
+            ticks: 0,
+            elapsed: Duration::ZERO,
+            next_seq: 0,
+            by_ticks: Default::default(),
+            by_time: Default::default(),
+        }
+    }
+}
+
+/// An item as stored in a [`Queue`], along with its tracing context when the
+/// `trace` feature is enabled.
+#[cfg(feature = "trace")]
+type Item<T> = Traced<T>;
+#[cfg(not(feature = "trace"))]
+type Item<T> = T;
+
+#[cfg(feature = "trace")]
+fn wrap<T>(value: T, system: Option<&str>) -> Item<T> {
+    Traced::new(value, system)
+}
+
+#[cfg(not(feature = "trace"))]
+fn wrap<T>(value: T, _system: Option<&str>) -> Item<T> {
+    value
+}
+
+#[cfg(feature = "trace")]
+fn value<T>(item: &Item<T>) -> &T {
+    item.value()
+}
+
+#[cfg(not(feature = "trace"))]
+fn value<T>(item: &Item<T>) -> &T {
+    item
+}
+
+#[cfg(feature = "trace")]
+fn into_value<T>(item: Item<T>) -> T {
+    item.span().in_scope(|| trace!("popped"));
+    item.into_inner()
+}
+
+#[cfg(not(feature = "trace"))]
+fn into_value<T>(item: Item<T>) -> T {
+    item
+}
+
+/// Source of the stamps ordering pushes across the queues read by a
+/// [`MergedQueueReader`](crate::MergedQueueReader). Other queues don't take a
+/// stamp, so their pushes don't contend on it.
+static NEXT_PUSH: AtomicU64 = AtomicU64::new(0);
+
+/// Counters describing the traffic through a [`Queue`].
+///
+/// Created by calling [`Queue::stats`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QueueStats {
+    /// Total number of items pushed.
+    pub pushes: u64,
+    /// Total number of items popped.
+    pub pops: u64,
+    /// Number of items currently in the queue.
+    pub len: usize,
+    /// The largest number of items the queue has held at once.
+    pub max_len: usize,
+}
 
 /// A multi-producer multi-consumer queue.
 #[derive(Resource)]
 pub struct Queue<T> {
-    q: SegQueue<T>,
+    shared: Arc<Shared<T>>,
+}
+
+/// State of a [`Queue`], shared with its [`QueueSender`]s.
+struct Shared<T> {
+    /// Items stamped with the order they were pushed in across every stamped
+    /// queue, or with `0` if stamping isn't enabled.
+    q: SegQueue<(u64, Item<T>)>,
+    stamped: AtomicBool,
+    /// Held for writing while the items are taken out of `q` to be visited, to
+    /// hold back pushes and pops until they have been put back.
+    gate: RwLock<()>,
+    delayed: Mutex<Delayed<Item<T>>>,
+    pushes: AtomicU64,
+    pops: AtomicU64,
+    len: AtomicUsize,
+    max_len: AtomicUsize,
+    drained: AtomicBool,
+    waiting: AtomicBool,
+    wakers: Mutex<Vec<Waker>>,
+    hooked: AtomicBool,
+    hooks: Mutex<PushHooks<T>>,
+}
+
+type RecordFn<T> = Box<dyn FnMut(&T) + Send>;
+
+/// Identifies a forwarder added with `Queue::add_forwarder`, so that items it
+/// received from elsewhere aren't forwarded back through it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct ForwarderId(u64);
+
+impl ForwarderId {
+    #[cfg(feature = "app")]
+    pub(crate) fn new() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// Hooks that observe or suppress pushes, used to record and replay queues and
+/// to forward them to other processes.
+struct PushHooks<T> {
+    record: Option<RecordFn<T>>,
+    forward: Vec<(ForwarderId, RecordFn<T>)>,
+    suppress: bool,
+}
+
+impl<T> Default for PushHooks<T> {
+    fn default() -> Self {
+        Self {
+            record: None,
+            forward: Vec::new(),
+            suppress: false,
+        }
+    }
 }
 
 impl<T> Default for Queue<T> {
     fn default() -> Self {
         Self {
-            q: Default::default(),
+            shared: Arc::new(Shared {
+                q: Default::default(),
+                gate: Default::default(),
+                stamped: AtomicBool::new(false),
+                delayed: Default::default(),
+                pushes: AtomicU64::new(0),
+                pops: AtomicU64::new(0),
+                len: AtomicUsize::new(0),
+                max_len: AtomicUsize::new(0),
+                drained: AtomicBool::new(false),
+                waiting: AtomicBool::new(false),
+                wakers: Default::default(),
+                hooked: AtomicBool::new(false),
+                hooks: Default::default(),
+            }),
+        }
+    }
+}
+
+impl<T> Shared<T> {
+    fn push(&self, value: T, system: Option<&str>) {
+        self.push_hooked(wrap(value, system), None);
+    }
+
+    /// Push an item through the hooks, skipping the forwarder the item was
+    /// received from, if any.
+    fn push_hooked(&self, item: Item<T>, source: Option<ForwarderId>) {
+        if self.hooked.load(Ordering::Relaxed) {
+            let mut hooks = self.hooks.lock().unwrap();
+            if hooks.suppress {
+                return;
+            }
+            if let Some(record) = &mut hooks.record {
+                record(value(&item));
+            }
+            for (id, forward) in &mut hooks.forward {
+                if source != Some(*id) {
+                    forward(value(&item));
+                }
+            }
+        }
+        self.push_unhooked(item);
+    }
+
+    /// Push an item, bypassing any recording or suppression.
+    fn push_unhooked(&self, value: Item<T>) {
+        // Count the item before it becomes visible so `len` never underflows.
+        let len = self.len.fetch_add(1, Ordering::Relaxed) + 1;
+        self.max_len.fetch_max(len, Ordering::Relaxed);
+        self.pushes.fetch_add(1, Ordering::Relaxed);
+        let stamp = if self.stamped.load(Ordering::Relaxed) {
+            NEXT_PUSH.fetch_add(1, Ordering::Relaxed)
+        } else {
+            0
+        };
+        {
+            let _gate = self.gate.read().unwrap();
+            self.q.push((stamp, value));
+        }
+
+        // Make the push visible before checking for waiting receivers, pairing
+        // with the fence in `Shared::poll_pop`.
+        fence(Ordering::SeqCst);
+        if self.waiting.load(Ordering::Relaxed) {
+            self.wake();
+        }
+    }
+
+    fn push_delayed(&self, value: T, delay: impl Into<Delay>, system: Option<&str>) {
+        let delay = delay.into();
+        let value = wrap(value, system);
+        // Items with no delay are already due, so don't wait for the next
+        // advance to release them.
+        if matches!(delay, Delay::Ticks(0) | Delay::Time(Duration::ZERO)) {
+            self.push_hooked(value, None);
+            return;
+        }
+        let mut delayed = self.delayed.lock().unwrap();
+        let seq = delayed.next_seq;
+        delayed.next_seq += 1;
+        match delay {
+            Delay::Ticks(ticks) => {
+                let due = delayed.ticks.saturating_add(ticks);
+                delayed.by_ticks.insert((due, seq), value);
+            }
+            Delay::Time(duration) => {
+                let due = delayed.elapsed.saturating_add(duration);
+                delayed.by_time.insert((due, seq), value);
+            }
+        }
+    }
+
+    fn pop(&self) -> Option<Item<T>> {
+        self.pop_stamped().map(|(_, item)| item)
+    }
+
+    /// Pop an item along with the stamp of its push.
+    fn pop_stamped(&self) -> Option<(u64, Item<T>)> {
+        let popped = {
+            let _gate = self.gate.read().unwrap();
+            self.q.pop()
+        };
+        match popped {
+            Some(value) => {
+                self.pops.fetch_add(1, Ordering::Relaxed);
+                if self.len.fetch_sub(1, Ordering::Relaxed) == 1 {
+                    self.drained.store(true, Ordering::Relaxed);
+                }
+                Some(value)
+            }
+            None => {
+                self.drained.store(true, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    /// Call `f` with every pending item, in order, leaving the items in place
+    /// with their stamps and tracing context.
+    fn for_each_pending(&self, mut f: impl FnMut(&T)) {
+        let _gate = self.gate.write().unwrap();
+        let items: Vec<_> = std::iter::from_fn(|| self.q.pop()).collect();
+        for (_, item) in &items {
+            f(value(item));
+        }
+        for item in items {
+            self.q.push(item);
+        }
+    }
+
+    #[cfg(feature = "app")]
+    fn update_hooks(&self, f: impl FnOnce(&mut PushHooks<T>)) {
+        let mut hooks = self.hooks.lock().unwrap();
+        f(&mut hooks);
+        let hooked = hooks.record.is_some() || !hooks.forward.is_empty() || hooks.suppress;
+        self.hooked.store(hooked, Ordering::Relaxed);
+    }
+
+    /// Register a waker to be woken by the next push.
+    fn register(&self, waker: &Waker) {
+        let mut wakers = self.wakers.lock().unwrap();
+        if !wakers.iter().any(|w| w.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+        self.waiting.store(true, Ordering::Relaxed);
+    }
+
+    /// Pop an item, or register the waker to be woken by the next push.
+    fn poll_pop(&self, cx: &mut Context<'_>) -> Poll<T> {
+        if let Some(item) = self.pop() {
+            return Poll::Ready(into_value(item));
+        }
+        self.register(cx.waker());
+
+        // Check again in case an item was pushed before the waker was
+        // registered, pairing with the fence in `Shared::push_unhooked`.
+        fence(Ordering::SeqCst);
+        match self.pop() {
+            Some(item) => Poll::Ready(into_value(item)),
+            None => Poll::Pending,
+        }
+    }
+
+    fn wake(&self) {
+        let wakers = {
+            let mut wakers = self.wakers.lock().unwrap();
+            self.waiting.store(false, Ordering::Relaxed);
+            std::mem::take(&mut *wakers)
+        };
+        for waker in wakers {
+            waker.wake();
         }
     }
 }
@@ -25,45 +359,382 @@ impl<T> Queue<T> {
 
     // Test if the queue is empty.
     pub fn is_empty(&self) -> bool {
-        self.q.is_empty()
+        self.shared.q.is_empty()
+    }
+
+    /// The number of items in the queue, not counting delayed items that are
+    /// not yet due.
+    pub fn len(&self) -> usize {
+        self.shared.len.load(Ordering::Relaxed)
+    }
+
+    /// Counters describing the traffic through the queue so far.
+    pub fn stats(&self) -> QueueStats {
+        QueueStats {
+            pushes: self.shared.pushes.load(Ordering::Relaxed),
+            pops: self.shared.pops.load(Ordering::Relaxed),
+            len: self.shared.len.load(Ordering::Relaxed),
+            max_len: self.shared.max_len.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Test if the queue has been drained since the last call, clearing the
+    /// flag. A queue is drained when popping from it finds it empty, or pops
+    /// its last item.
+    pub fn take_drained(&self) -> bool {
+        self.shared.drained.swap(false, Ordering::Relaxed)
     }
 
     // Push an item onto the queue.
     pub fn push(&self, value: T) {
-        self.q.push(value)
+        self.shared.push(value, None)
+    }
+
+    /// Push an item onto the queue once the given delay has passed. Until then,
+    /// the item is held back and is not visible to readers. Items that become
+    /// due at the same time are pushed in the order they were delayed, and items
+    /// with no delay are pushed immediately.
+    pub fn push_delayed(&self, value: T, delay: impl Into<Delay>) {
+        self.shared.push_delayed(value, delay, None)
+    }
+
+    /// The number of delayed items that are not yet due.
+    pub fn delayed_len(&self) -> usize {
+        let delayed = self.shared.delayed.lock().unwrap();
+        delayed.by_ticks.len() + delayed.by_time.len()
+    }
+
+    /// Advance the queue's tick count by one, pushing any items delayed with
+    /// [`Delay::Ticks`] that are now due.
+    pub fn tick(&self) {
+        self.advance_ticks(1);
     }
 
-    // Pop an item off of the queue. Returns `None` if the queue is empty.
+    /// Advance the queue's tick count, pushing any items delayed with
+    /// [`Delay::Ticks`] that are now due.
+    pub fn advance_ticks(&self, ticks: u64) {
+        let mut delayed = self.shared.delayed.lock().unwrap();
+        delayed.ticks = delayed.ticks.saturating_add(ticks);
+        let now = (delayed.ticks, u64::MAX);
+        while let Some(entry) = delayed.by_ticks.first_entry() {
+            if *entry.key() > now {
+                break;
+            }
+            self.shared.push_hooked(entry.remove(), None);
+        }
+    }
+
+    /// Advance the queue's elapsed time, pushing any items delayed with
+    /// [`Delay::Time`] that are now due.
+    ///
+    /// When added with the `QueuePlugin`, this is called every frame with
+    /// Bevy's `Time`.
+    pub fn advance_time(&self, delta: Duration) {
+        let mut delayed = self.shared.delayed.lock().unwrap();
+        delayed.elapsed = delayed.elapsed.saturating_add(delta);
+        let now = (delayed.elapsed, u64::MAX);
+        while let Some(entry) = delayed.by_time.first_entry() {
+            if *entry.key() > now {
+                break;
+            }
+            self.shared.push_hooked(entry.remove(), None);
+        }
+    }
+
+    /// Pop an item off of the queue. Returns `None` if the queue is empty.
+    ///
+    /// With the `trace` feature, the span of the item's push is only entered
+    /// to record that it was popped. Use [`Queue::iter`] or `pop_traced` to
+    /// handle the item inside its span.
     pub fn pop(&self) -> Option<T> {
-        self.q.pop()
+        self.shared.pop().map(into_value)
+    }
+
+    /// Pop an item off of the queue along with the tracing context of its
+    /// push. Returns `None` if the queue is empty.
+    #[cfg(feature = "trace")]
+    pub fn pop_traced(&self) -> Option<Traced<T>> {
+        self.shared.pop()
+    }
+
+    /// Pop every item off of the queue, calling `f` with each inside the span
+    /// of its push, so that items pushed by `f` are recorded as children of
+    /// the item being handled.
+    #[cfg(feature = "trace")]
+    pub fn for_each_in_span(&self, mut f: impl FnMut(T)) {
+        while let Some(item) = self.shared.pop() {
+            let (value, span) = item.into_parts();
+            span.in_scope(|| {
+                trace!("popped");
+                f(value)
+            });
+        }
+    }
+
+    /// Call `f` with every pending item, in order, without popping them, such
+    /// as to save them.
+    ///
+    /// Pushes and pops from other threads wait until `f` has seen every item,
+    /// so `f` must not push onto or pop from the queue itself.
+    pub fn for_each_pending(&self, f: impl FnMut(&T)) {
+        self.shared.for_each_pending(f)
+    }
+
+    /// Replace the pending items with `items`, in order, such as when loading
+    /// a saved game.
+    ///
+    /// The items bypass recording, replay suppression and forwarding, since
+    /// they were already seen when they were first pushed.
+    pub fn restore(&self, items: impl IntoIterator<Item = T>) {
+        while self.shared.pop().is_some() {}
+        for item in items {
+            self.shared.push_unhooked(wrap(item, None));
+        }
+    }
+
+    /// Wait for an item to be pushed onto the queue and pop it.
+    ///
+    /// Useful from async tasks. Systems that never await are unaffected, and
+    /// can keep using [`Queue::pop`].
+    pub fn recv(&self) -> Recv<'_, T> {
+        Recv {
+            shared: &self.shared,
+        }
+    }
+
+    /// Call `record` with every item pushed onto the queue, or stop recording
+    /// if `None`.
+    #[cfg(feature = "record")]
+    pub(crate) fn set_recorder(&self, record: Option<RecordFn<T>>) {
+        self.shared.update_hooks(|hooks| hooks.record = record);
+    }
+
+    /// Drop every item pushed onto the queue, except those pushed with
+    /// [`Queue::push_unhooked`].
+    #[cfg(feature = "record")]
+    pub(crate) fn set_suppressed(&self, suppress: bool) {
+        self.shared.update_hooks(|hooks| hooks.suppress = suppress);
     }
 
-    // Iterate over items in the queue. This drains the queue, but does not consume
-    // the `Queue` itself.
-    pub fn iter(&self) -> QueueIter<T> {
+    /// Push an item, bypassing any recording or suppression.
+    #[cfg(feature = "record")]
+    pub(crate) fn push_unhooked(&self, value: T) {
+        self.shared.push_unhooked(wrap(value, None))
+    }
+
+    /// Call `forward` with every item pushed onto the queue, except those
+    /// received through the forwarder with the same `id`, along with any other
+    /// forwarders already added.
+    #[cfg(feature = "app")]
+    pub(crate) fn add_forwarder(&self, id: ForwarderId, forward: RecordFn<T>) {
+        self.shared
+            .update_hooks(|hooks| hooks.forward.push((id, forward)));
+    }
+
+    /// Push an item received from elsewhere, such as Bevy's events, through
+    /// every forwarder except the one it came from.
+    #[cfg(feature = "app")]
+    pub(crate) fn push_received(&self, value: T, source: ForwarderId) {
+        self.shared.push_hooked(wrap(value, None), Some(source))
+    }
+
+    /// Stamp every item pushed from now on with the order of its push
+    /// relative to pushes onto every other stamped queue, so that a
+    /// [`MergedQueueReader`](crate::MergedQueueReader) can put them in order.
+    ///
+    /// This is done when a merged reader of the queue is initialized. Call it
+    /// beforehand to also order items pushed before the reader's first run,
+    /// such as from `Startup` systems.
+    pub fn enable_stamping(&self) {
+        if !self.shared.stamped.load(Ordering::Relaxed) {
+            self.shared.stamped.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Pop an item off of the queue along with the stamp ordering its push
+    /// relative to pushes onto every other stamped queue.
+    pub(crate) fn pop_stamped(&self) -> Option<(u64, T)> {
+        self.shared
+            .pop_stamped()
+            .map(|(stamp, item)| (stamp, into_value(item)))
+    }
+
+    /// Create a handle for pushing onto the queue from outside of the ECS, such
+    /// as from other threads or async tasks.
+    pub fn sender(&self) -> QueueSender<T> {
+        QueueSender {
+            shared: self.shared.clone(),
+        }
+    }
+
+    /// Create a handle for popping from the queue in async tasks, as a
+    /// [`Stream`] or with [`QueueReceiver::recv`].
+    pub fn receiver(&self) -> QueueReceiver<T> {
+        QueueReceiver {
+            shared: self.shared.clone(),
+        }
+    }
+
+    /// Iterate over items in the queue. This drains the queue, but does not
+    /// consume the `Queue` itself.
+    ///
+    /// With the `trace` feature, the span of each item's push is entered from
+    /// when the item is returned until the next item is taken or the iterator
+    /// is dropped.
+    pub fn iter(&self) -> QueueIter<'_, T> {
         QueueIter::new(self)
     }
 }
 
+/// A cloneable handle for pushing onto a [`Queue`] from anywhere, including
+/// other threads and async tasks.
+///
+/// Created by calling [`Queue::sender`].
+pub struct QueueSender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Clone for QueueSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> QueueSender<T> {
+    /// Push an item onto the `Queue`.
+    pub fn push(&self, value: T) {
+        self.shared.push(value, None)
+    }
+
+    /// Push an item received from another process through every forwarder
+    /// except the one it came from.
+    #[cfg(feature = "net")]
+    pub(crate) fn push_received(&self, value: T, source: ForwarderId) {
+        self.shared.push_hooked(wrap(value, None), Some(source))
+    }
+
+    /// Push an item onto the `Queue` once the given delay has passed.
+    pub fn push_delayed(&self, value: T, delay: impl Into<Delay>) {
+        self.shared.push_delayed(value, delay, None)
+    }
+}
+
+/// Future that resolves to the next item pushed onto a [`Queue`].
+///
+/// Created by calling [`Queue::recv`] or [`QueueReceiver::recv`].
+pub struct Recv<'a, T> {
+    shared: &'a Shared<T>,
+}
+
+impl<'a, T> Future for Recv<'a, T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.shared.poll_pop(cx)
+    }
+}
+
+/// A cloneable handle for popping from a [`Queue`] in async tasks, which can
+/// outlive a borrow of the `Queue`.
+///
+/// Implements [`Stream`], yielding items as they are pushed. The stream never
+/// ends, since items can always be pushed through the queue.
+///
+/// Created by calling [`Queue::receiver`].
+pub struct QueueReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Clone for QueueReceiver<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> QueueReceiver<T> {
+    /// Pop an item off of the `Queue`. Returns `None` if the queue is empty.
+    /// See [`Queue::pop`].
+    pub fn pop(&self) -> Option<T> {
+        self.shared.pop().map(into_value)
+    }
+
+    /// Wait for an item to be pushed onto the `Queue` and pop it.
+    pub fn recv(&self) -> Recv<'_, T> {
+        Recv {
+            shared: &self.shared,
+        }
+    }
+}
+
+impl<T> Stream for QueueReceiver<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.shared.poll_pop(cx).map(Some)
+    }
+}
+
 /// An iterator for `Queue`.
 ///
 /// Created by calling [`Queue::iter`]. See its documentation for more.
+///
+/// With the `trace` feature, the iterator enters the span of each item's push
+/// while the item is being handled, so items pushed while handling it are
+/// children of its span. Like a span guard, it should not be held across an
+/// `.await`.
 pub struct QueueIter<'a, T> {
     q: &'a Queue<T>,
+    /// The span of the item last returned, which is entered.
+    #[cfg(feature = "trace")]
+    entered: Option<Span>,
 }
 
 impl<'a, T> QueueIter<'a, T> {
     fn new(q: &'a Queue<T>) -> Self {
-        Self { q }
+        Self {
+            q,
+            #[cfg(feature = "trace")]
+            entered: None,
+        }
+    }
+
+    /// Exit the span of the item last returned.
+    #[cfg(feature = "trace")]
+    fn exit(&mut self) {
+        if let Some(span) = self.entered.take() {
+            span.with_subscriber(|(id, dispatch)| dispatch.exit(id));
+        }
     }
 }
 
 impl<'a, T> Iterator for QueueIter<'a, T> {
     type Item = T;
 
+    #[cfg(not(feature = "trace"))]
     fn next(&mut self) -> Option<Self::Item> {
         self.q.pop()
     }
+
+    #[cfg(feature = "trace")]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.exit();
+        let (value, span) = self.q.shared.pop()?.into_parts();
+        span.with_subscriber(|(id, dispatch)| dispatch.enter(id));
+        trace!("popped");
+        self.entered = Some(span);
+        Some(value)
+    }
+}
+
+#[cfg(feature = "trace")]
+impl<'a, T> Drop for QueueIter<'a, T> {
+    fn drop(&mut self) {
+        self.exit();
+    }
 }
 
 /// Push items onto a `Queue`.
@@ -76,6 +747,8 @@ where
     E: Send + 'static,
 {
     queue: Res<'w, Queue<E>>,
+    #[cfg(feature = "trace")]
+    system: SystemName<'s>,
     #[system_param(ignore)]
     marker: PhantomData<&'s ()>,
 }
@@ -86,7 +759,23 @@ where
 {
     /// Push an item onto the `Queue`.
     pub fn push(&self, value: E) {
-        self.queue.push(value)
+        self.queue.shared.push(value, self.system())
+    }
+
+    /// Push an item onto the `Queue` once the given delay has passed.
+    pub fn push_delayed(&self, value: E, delay: impl Into<Delay>) {
+        self.queue.shared.push_delayed(value, delay, self.system())
+    }
+
+    /// The name of the system pushing items, recorded in their tracing context.
+    #[cfg(feature = "trace")]
+    fn system(&self) -> Option<&str> {
+        Some(self.system.name())
+    }
+
+    #[cfg(not(feature = "trace"))]
+    fn system(&self) -> Option<&str> {
+        None
     }
 }
 
@@ -113,16 +802,23 @@ where
         self.queue.is_empty()
     }
 
-    /// Pop the top item off of the queue.
+    /// Pop the top item off of the queue. See [`Queue::pop`].
     pub fn pop(&self) -> Option<E> {
         self.queue.pop()
     }
 
     /// Iterate over through items in the `Queue`. This drains the `queue`, but
-    /// does not consume the underlying `Queue`.
-    pub fn iter(&self) -> QueueIter<E> {
+    /// does not consume the underlying `Queue`. See [`Queue::iter`].
+    pub fn iter(&self) -> QueueIter<'_, E> {
         self.queue.iter()
     }
+
+    /// Pop every item off of the queue, calling `f` with each inside the span
+    /// of its push. See [`Queue::for_each_in_span`].
+    #[cfg(feature = "trace")]
+    pub fn for_each_in_span(&self, f: impl FnMut(E)) {
+        self.queue.for_each_in_span(f)
+    }
 }
 
 /// Trait for pushing items to a [`Queue`].
@@ -150,3 +846,280 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::task::Wake;
+    use std::thread::{self, Thread};
+
+    use super::*;
+
+    /// Wakes the thread blocked on a future.
+    struct Unpark {
+        thread: Thread,
+        woken: AtomicBool,
+    }
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.woken.store(true, Ordering::SeqCst);
+            self.thread.unpark();
+        }
+    }
+
+    /// Run a future to completion on the current thread, failing if it is not
+    /// woken within a few seconds of returning `Pending`.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let unpark = Arc::new(Unpark {
+            thread: thread::current(),
+            woken: AtomicBool::new(false),
+        });
+        let waker = Waker::from(unpark.clone());
+        let mut cx = Context::from_waker(&waker);
+        let mut future = std::pin::pin!(future);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+            let timeout = std::time::Instant::now() + Duration::from_secs(5);
+            while !unpark.woken.swap(false, Ordering::SeqCst) {
+                let now = std::time::Instant::now();
+                assert!(now < timeout, "future was never woken");
+                thread::park_timeout(timeout - now);
+            }
+        }
+    }
+
+    #[test]
+    fn sender_is_clone_send_static() {
+        fn assert_sender<S: Clone + Send + Sync + 'static>() {}
+        assert_sender::<QueueSender<u32>>();
+    }
+
+    #[test]
+    fn recv_returns_pending_items() {
+        let queue = Queue::new();
+        queue.push(1);
+        assert_eq!(block_on(queue.recv()), 1);
+    }
+
+    #[test]
+    fn recv_wakes_on_push_from_other_thread() {
+        let queue = Queue::new();
+        let sender = queue.sender();
+        let pusher = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            sender.push(1);
+        });
+        assert_eq!(block_on(queue.recv()), 1);
+        pusher.join().unwrap();
+    }
+
+    #[test]
+    fn recv_with_concurrent_senders() {
+        const SENDERS: usize = 4;
+        const ITEMS: usize = 2000;
+
+        let queue = Queue::new();
+        let pushers: Vec<_> = (0..SENDERS)
+            .map(|sender_id| {
+                let sender = queue.sender();
+                thread::spawn(move || {
+                    for i in 0..ITEMS {
+                        sender.push(sender_id * ITEMS + i);
+                        if i % 100 == 0 {
+                            thread::yield_now();
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        let mut received: Vec<_> = (0..SENDERS * ITEMS)
+            .map(|_| block_on(queue.recv()))
+            .collect();
+        for pusher in pushers {
+            pusher.join().unwrap();
+        }
+        received.sort_unstable();
+        assert_eq!(received, (0..SENDERS * ITEMS).collect::<Vec<_>>());
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn recv_with_several_receivers() {
+        const RECEIVERS: usize = 3;
+        const ITEMS: usize = 1000;
+
+        let queue = Queue::new();
+        let sender = queue.sender();
+        let received = AtomicUsize::new(0);
+        let sum = AtomicUsize::new(0);
+        thread::scope(|scope| {
+            for _ in 0..RECEIVERS {
+                scope.spawn(|| {
+                    for _ in 0..ITEMS {
+                        sum.fetch_add(block_on(queue.recv()), Ordering::Relaxed);
+                        received.fetch_add(1, Ordering::Relaxed);
+                    }
+                });
+            }
+            for i in 0..RECEIVERS * ITEMS {
+                sender.push(i);
+            }
+        });
+        let n = RECEIVERS * ITEMS;
+        assert_eq!(received.into_inner(), n);
+        assert_eq!(sum.into_inner(), n * (n - 1) / 2);
+    }
+
+    #[test]
+    fn zero_delay_pushes_immediately() {
+        let queue = Queue::new();
+        queue.push_delayed(1, Delay::Ticks(0));
+        queue.push_delayed(2, Duration::ZERO);
+        queue.push_delayed(3, Delay::Ticks(1));
+        assert_eq!(queue.delayed_len(), 1);
+        assert_eq!(queue.iter().collect::<Vec<_>>(), [1, 2]);
+
+        queue.tick();
+        assert_eq!(queue.iter().collect::<Vec<_>>(), [3]);
+    }
+
+    /// Wait for the next item from a stream.
+    fn next<S: Stream + Unpin>(stream: &mut S) -> impl Future<Output = Option<S::Item>> + '_ {
+        std::future::poll_fn(move |cx| Pin::new(&mut *stream).poll_next(cx))
+    }
+
+    #[test]
+    fn receiver_is_clone_send_static() {
+        fn assert_receiver<R: Clone + Send + Sync + 'static>() {}
+        assert_receiver::<QueueReceiver<u32>>();
+    }
+
+    #[test]
+    fn stream_wakes_after_push() {
+        let queue = Queue::new();
+        let mut receiver = queue.receiver();
+        let unpark = Arc::new(Unpark {
+            thread: thread::current(),
+            woken: AtomicBool::new(false),
+        });
+        let waker = Waker::from(unpark.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        assert!(Pin::new(&mut receiver).poll_next(&mut cx).is_pending());
+        assert!(!unpark.woken.load(Ordering::SeqCst));
+        queue.push(1);
+        assert!(unpark.woken.load(Ordering::SeqCst));
+        assert_eq!(
+            Pin::new(&mut receiver).poll_next(&mut cx),
+            Poll::Ready(Some(1))
+        );
+    }
+
+    #[test]
+    fn receiver_outlives_queue() {
+        let queue = Queue::new();
+        let sender = queue.sender();
+        let mut receiver = queue.receiver();
+        drop(queue);
+
+        let task = thread::spawn(move || {
+            let first = block_on(receiver.recv());
+            let second = block_on(next(&mut receiver));
+            (first, second)
+        });
+        thread::sleep(Duration::from_millis(20));
+        sender.push(1);
+        sender.push(2);
+        assert_eq!(task.join().unwrap(), (1, Some(2)));
+    }
+
+    #[test]
+    fn receivers_compete_for_items() {
+        const ITEMS: usize = 2000;
+
+        let queue = Queue::new();
+        let sender = queue.sender();
+        let receivers: Vec<_> = (0..2)
+            .map(|_| {
+                let mut receiver = queue.receiver();
+                thread::spawn(move || {
+                    let mut received = Vec::new();
+                    loop {
+                        match block_on(next(&mut receiver)) {
+                            Some(usize::MAX) => break,
+                            Some(i) => received.push(i),
+                            None => unreachable!("queue streams never end"),
+                        }
+                    }
+                    received
+                })
+            })
+            .collect();
+
+        for i in 0..ITEMS {
+            sender.push(i);
+        }
+        // One stop marker for each receiver.
+        sender.push(usize::MAX);
+        sender.push(usize::MAX);
+
+        let mut received: Vec<_> = receivers
+            .into_iter()
+            .flat_map(|receiver| receiver.join().unwrap())
+            .collect();
+        received.sort_unstable();
+        assert_eq!(received, (0..ITEMS).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn for_each_pending_leaves_items_in_place() {
+        let queue = Queue::new();
+        let later = Queue::new();
+        queue.enable_stamping();
+        later.enable_stamping();
+        for i in 0..3 {
+            queue.push(i);
+        }
+        later.push(3);
+
+        let mut seen = Vec::new();
+        queue.for_each_pending(|i| seen.push(*i));
+        assert_eq!(seen, [0, 1, 2]);
+        assert_eq!(queue.stats().pops, 0);
+
+        // The items keep the stamps of their original pushes.
+        let (later_stamp, _) = later.pop_stamped().unwrap();
+        let popped: Vec<_> = std::iter::from_fn(|| queue.pop_stamped()).collect();
+        assert_eq!(
+            popped.iter().map(|(_, i)| *i).collect::<Vec<_>>(),
+            [0, 1, 2]
+        );
+        assert!(popped.iter().all(|(stamp, _)| *stamp < later_stamp));
+    }
+
+    #[cfg(feature = "app")]
+    #[test]
+    fn saving_and_restoring_skips_hooks() {
+        let queue = Queue::new();
+        let forwarded = Arc::new(AtomicUsize::new(0));
+        let counter = forwarded.clone();
+        queue.add_forwarder(
+            ForwarderId::new(),
+            Box::new(move |_: &u32| {
+                counter.fetch_add(1, Ordering::Relaxed);
+            }),
+        );
+        queue.push(1);
+        queue.push(2);
+
+        queue.for_each_pending(|_| {});
+        queue.restore([3, 4]);
+
+        assert_eq!(forwarded.load(Ordering::Relaxed), 2);
+        assert_eq!(queue.iter().collect::<Vec<_>>(), [3, 4]);
+    }
+}
@@ -0,0 +1,302 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use bevy_app::{App, First, Last, Plugin};
+use bevy_ecs::prelude::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tracing::{error, warn};
+
+use crate::Queue;
+
+type RecorderFn = dyn Fn(&mut App) + Send + Sync;
+
+/// Whether a [`QueueRecorderPlugin`] records or replays queue traffic.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RecorderMode {
+    /// Record every push to the selected queues to the file at the given path.
+    Record(PathBuf),
+    /// Replay the recording at the given path, suppressing live pushes to the
+    /// selected queues.
+    Replay(PathBuf),
+}
+
+/// A single recorded push.
+#[derive(Serialize, Deserialize)]
+struct RecordedMessage {
+    frame: u64,
+    queue: String,
+    message: String,
+}
+
+/// Plugin that records the traffic of selected [`Queue`]s to a file, or
+/// replays a recording, to reproduce a session deterministically.
+///
+/// In record mode, every push to the selected queues is written to the file,
+/// stamped with the frame it was pushed on. In replay mode, live pushes to the
+/// selected queues are dropped, and the recorded messages are pushed again at
+/// the start of the frame they were recorded on.
+///
+/// Queues are identified by their type name, so a recording can only be
+/// replayed by a build of the same game.
+pub struct QueueRecorderPlugin {
+    mode: RecorderMode,
+    types: Vec<Box<RecorderFn>>,
+}
+
+impl QueueRecorderPlugin {
+    /// Record queue traffic to the file at the given path.
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self {
+            mode: RecorderMode::Record(path.into()),
+            types: Vec::new(),
+        }
+    }
+
+    /// Replay queue traffic from the file at the given path.
+    pub fn replay(path: impl Into<PathBuf>) -> Self {
+        Self {
+            mode: RecorderMode::Replay(path.into()),
+            types: Vec::new(),
+        }
+    }
+
+    /// Record or replay the [`Queue`] of type `T`, adding it to the app if
+    /// needed.
+    pub fn with_queue<T>(mut self) -> Self
+    where
+        T: Serialize + DeserializeOwned + Send + 'static,
+    {
+        self.types.push(Box::new(|app: &mut App| {
+            app.init_resource::<Queue<T>>();
+            let recorder = app.world.resource::<QueueRecorder>();
+            if !recorder.is_active() {
+                return;
+            }
+            let queue = app.world.resource::<Queue<T>>();
+            match &recorder.mode {
+                RecorderMode::Record(_) => {
+                    let writer = recorder.writer.clone();
+                    let frame = recorder.frame.clone();
+                    queue.set_recorder(Some(Box::new(move |value: &T| {
+                        record(&writer, frame.load(Ordering::Relaxed), value)
+                    })));
+                }
+                RecorderMode::Replay(_) => {
+                    queue.set_suppressed(true);
+                    app.add_systems(First, replay_queue::<T>.after(advance_frame));
+                }
+            }
+        }));
+        self
+    }
+}
+
+impl Plugin for QueueRecorderPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(QueueRecorder::new(self.mode.clone()))
+            .add_systems(First, advance_frame)
+            .add_systems(Last, flush_recording);
+        for t in self.types.iter() {
+            t(app);
+        }
+    }
+}
+
+/// Resource holding the state of a [`QueueRecorderPlugin`].
+///
+/// If the recording can't be created or read, the error is logged and the
+/// recorder is left inactive, so the selected queues work as if there were no
+/// recorder.
+#[derive(Resource)]
+pub struct QueueRecorder {
+    mode: RecorderMode,
+    active: bool,
+    frame: Arc<AtomicU64>,
+    writer: Option<Arc<Mutex<BufWriter<File>>>>,
+    recorded: HashMap<String, VecDeque<(u64, String)>>,
+}
+
+impl QueueRecorder {
+    fn new(mode: RecorderMode) -> Self {
+        let mut recorder = Self {
+            mode: mode.clone(),
+            active: false,
+            frame: Default::default(),
+            writer: None,
+            recorded: HashMap::new(),
+        };
+        match mode {
+            RecorderMode::Record(path) => match File::create(&path) {
+                Ok(file) => {
+                    recorder.writer = Some(Arc::new(Mutex::new(BufWriter::new(file))));
+                    recorder.active = true;
+                }
+                Err(e) => error!("Failed to create queue recording at {:?}: {:?}", path, e),
+            },
+            RecorderMode::Replay(path) => {
+                let text = match fs::read_to_string(&path) {
+                    Ok(text) => text,
+                    Err(e) => {
+                        error!("Failed to read queue recording at {:?}: {:?}", path, e);
+                        return recorder;
+                    }
+                };
+                recorder.active = true;
+                for line in text.lines().filter(|line| !line.trim().is_empty()) {
+                    match ron::from_str::<RecordedMessage>(line) {
+                        Ok(recorded) => recorder
+                            .recorded
+                            .entry(recorded.queue)
+                            .or_default()
+                            .push_back((recorded.frame, recorded.message)),
+                        Err(e) => warn!("Skipping unreadable recorded message: {:?}", e),
+                    }
+                }
+            }
+        }
+        recorder
+    }
+
+    /// Whether the recorder is recording or replaying.
+    pub fn mode(&self) -> &RecorderMode {
+        &self.mode
+    }
+
+    /// Whether the recording was opened, and the selected queues are being
+    /// recorded or replayed.
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// The current frame, counted from when the recorder was added.
+    pub fn frame(&self) -> u64 {
+        self.frame.load(Ordering::Relaxed)
+    }
+
+    /// Test if every recorded message has been replayed. Always `true` when
+    /// recording.
+    pub fn is_finished(&self) -> bool {
+        self.recorded.values().all(VecDeque::is_empty)
+    }
+}
+
+fn record<T: Serialize>(writer: &Option<Arc<Mutex<BufWriter<File>>>>, frame: u64, value: &T) {
+    let Some(writer) = writer else {
+        return;
+    };
+    let recorded = ron::to_string(value).and_then(|message| {
+        ron::to_string(&RecordedMessage {
+            frame,
+            queue: std::any::type_name::<T>().to_string(),
+            message,
+        })
+    });
+    match recorded {
+        Ok(line) => {
+            if let Err(e) = writeln!(writer.lock().unwrap(), "{line}") {
+                error!("Failed to record message: {:?}", e);
+            }
+        }
+        Err(e) => error!("Failed to serialize recorded message: {:?}", e),
+    }
+}
+
+fn advance_frame(recorder: Res<QueueRecorder>) {
+    recorder.frame.fetch_add(1, Ordering::Relaxed);
+}
+
+fn flush_recording(recorder: Res<QueueRecorder>) {
+    if let Some(writer) = &recorder.writer {
+        if let Err(e) = writer.lock().unwrap().flush() {
+            error!("Failed to flush queue recording: {:?}", e);
+        }
+    }
+}
+
+fn replay_queue<T>(mut recorder: ResMut<QueueRecorder>, queue: Res<Queue<T>>)
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    let frame = recorder.frame();
+    let Some(recorded) = recorder.recorded.get_mut(std::any::type_name::<T>()) else {
+        return;
+    };
+    while recorded.front().is_some_and(|(due, _)| *due <= frame) {
+        let (_, message) = recorded.pop_front().unwrap();
+        match ron::from_str(&message) {
+            Ok(value) => queue.push_unhooked(value),
+            Err(e) => warn!("Skipping unreadable recorded message: {:?}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replays_recorded_pushes() {
+        let path =
+            std::env::temp_dir().join(format!("rouge_queue_recording_{}.ron", std::process::id()));
+
+        let mut app = App::new();
+        app.add_plugins(QueueRecorderPlugin::record(&path).with_queue::<u32>());
+        for pushes in [&[1, 2][..], &[3], &[], &[4], &[]] {
+            app.update();
+            let queue = app.world.resource::<Queue<u32>>();
+            for value in pushes {
+                queue.push(*value);
+            }
+            queue.iter().for_each(drop);
+        }
+        drop(app);
+
+        let mut app = App::new();
+        app.add_plugins(QueueRecorderPlugin::replay(&path).with_queue::<u32>());
+        let mut replayed = Vec::new();
+        for _ in 0..5 {
+            app.update();
+            let queue = app.world.resource::<Queue<u32>>();
+            queue.push(100);
+            let frame = app.world.resource::<QueueRecorder>().frame();
+            replayed.extend(queue.iter().map(|value| (frame, value)));
+        }
+        let finished = app.world.resource::<QueueRecorder>().is_finished();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(replayed, [(1, 1), (1, 2), (2, 3), (4, 4)]);
+        assert!(finished);
+    }
+
+    #[test]
+    fn missing_replay_leaves_queues_live() {
+        let mut app = App::new();
+        app.add_plugins(
+            QueueRecorderPlugin::replay("rouge_queue_missing_recording.ron").with_queue::<u32>(),
+        );
+        assert!(!app.world.resource::<QueueRecorder>().is_active());
+
+        let queue = app.world.resource::<Queue<u32>>();
+        queue.push(1);
+        assert_eq!(queue.pop(), Some(1));
+    }
+
+    #[test]
+    fn bad_record_path_leaves_queues_live() {
+        let mut app = App::new();
+        app.add_plugins(
+            QueueRecorderPlugin::record("rouge_queue_missing_dir/recording.ron")
+                .with_queue::<u32>(),
+        );
+        assert!(!app.world.resource::<QueueRecorder>().is_active());
+
+        let queue = app.world.resource::<Queue<u32>>();
+        queue.push(1);
+        assert_eq!(queue.pop(), Some(1));
+        app.update();
+    }
+}
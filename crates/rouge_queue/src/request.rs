@@ -0,0 +1,237 @@
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use crate::{Queue, QueueReader, QueueSender, QueueWriter};
+
+/// A [`Queue`] of requests, each paired with the [`Responder`] used to answer
+/// it.
+pub type RequestQueue<Req, R> = Queue<(Req, Responder<R>)>;
+
+/// `SystemParam` for pushing requests onto a [`RequestQueue`].
+pub type RequestWriter<'w, 's, Req, R> = QueueWriter<'w, 's, (Req, Responder<R>)>;
+
+/// `SystemParam` for reading `(request, responder)` pairs from a
+/// [`RequestQueue`].
+pub type RequestReader<'w, 's, Req, R> = QueueReader<'w, 's, (Req, Responder<R>)>;
+
+/// Error returned by a [`Reply`] when its [`Responder`] was dropped without
+/// answering the request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unanswered;
+
+impl fmt::Display for Unanswered {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "request was dropped without a response")
+    }
+}
+
+impl std::error::Error for Unanswered {}
+
+struct Slot<R> {
+    value: Option<R>,
+    answered: bool,
+    closed: bool,
+    waker: Option<Waker>,
+}
+
+/// Create a connected [`Responder`] and [`Reply`].
+fn oneshot<R>() -> (Responder<R>, Reply<R>) {
+    let slot = Arc::new(Mutex::new(Slot {
+        value: None,
+        answered: false,
+        closed: false,
+        waker: None,
+    }));
+    (Responder { slot: slot.clone() }, Reply { slot })
+}
+
+/// Handle for the response to a request pushed with `push_request`.
+///
+/// Poll it with [`Reply::try_take`] from a later system, or `.await` it from
+/// async code.
+pub struct Reply<R> {
+    slot: Arc<Mutex<Slot<R>>>,
+}
+
+impl<R> Reply<R> {
+    /// Take the response if the request has been answered.
+    ///
+    /// Returns `Ok(None)` while the request is pending, and after the response
+    /// has been taken. Returns [`Unanswered`] if the request was dropped
+    /// without a response.
+    pub fn try_take(&self) -> Result<Option<R>, Unanswered> {
+        let mut slot = self.slot.lock().unwrap();
+        match slot.value.take() {
+            Some(value) => Ok(Some(value)),
+            None if slot.closed && !slot.answered => Err(Unanswered),
+            None => Ok(None),
+        }
+    }
+
+    /// Test if the request has been answered, or dropped without a response.
+    pub fn is_done(&self) -> bool {
+        let slot = self.slot.lock().unwrap();
+        slot.answered || slot.closed
+    }
+}
+
+impl<R> Future for Reply<R> {
+    type Output = Result<R, Unanswered>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut slot = self.slot.lock().unwrap();
+        match slot.value.take() {
+            Some(value) => Poll::Ready(Ok(value)),
+            None if slot.closed && !slot.answered => Poll::Ready(Err(Unanswered)),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Answers a single request read from a [`RequestQueue`].
+///
+/// Dropping it without calling [`Responder::respond`] marks the request as
+/// [`Unanswered`].
+pub struct Responder<R> {
+    slot: Arc<Mutex<Slot<R>>>,
+}
+
+impl<R> Responder<R> {
+    /// Send the response to the [`Reply`].
+    pub fn respond(self, value: R) {
+        let mut slot = self.slot.lock().unwrap();
+        slot.value = Some(value);
+        slot.answered = true;
+    }
+
+    /// Test if the [`Reply`] has been dropped, meaning nobody is waiting for
+    /// the response.
+    pub fn is_abandoned(&self) -> bool {
+        Arc::strong_count(&self.slot) == 1
+    }
+}
+
+impl<R> Drop for Responder<R> {
+    fn drop(&mut self) {
+        let mut slot = self.slot.lock().unwrap();
+        slot.closed = true;
+        if let Some(waker) = slot.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<Req, R> Queue<(Req, Responder<R>)>
+where
+    Req: Send + 'static,
+    R: Send + 'static,
+{
+    /// Push a request onto the queue, returning the [`Reply`] handle for its
+    /// response.
+    pub fn push_request(&self, request: Req) -> Reply<R> {
+        let (responder, reply) = oneshot();
+        self.push((request, responder));
+        reply
+    }
+}
+
+impl<Req, R> QueueSender<(Req, Responder<R>)>
+where
+    Req: Send + 'static,
+    R: Send + 'static,
+{
+    /// Push a request onto the queue, returning the [`Reply`] handle for its
+    /// response.
+    pub fn push_request(&self, request: Req) -> Reply<R> {
+        let (responder, reply) = oneshot();
+        self.push((request, responder));
+        reply
+    }
+}
+
+impl<'w, 's, Req, R> QueueWriter<'w, 's, (Req, Responder<R>)>
+where
+    Req: Send + 'static,
+    R: Send + 'static,
+{
+    /// Push a request onto the queue, returning the [`Reply`] handle for its
+    /// response.
+    pub fn push_request(&self, request: Req) -> Reply<R> {
+        let (responder, reply) = oneshot();
+        self.push((request, responder));
+        reply
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::task::Wake;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Flag(AtomicBool);
+
+    impl Wake for Flag {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn responses_reach_their_requests() {
+        let queue = RequestQueue::<u32, String>::default();
+        let replies: Vec<_> = (1..=3).map(|i| queue.push_request(i)).collect();
+        assert!(replies.iter().all(|reply| !reply.is_done()));
+
+        let mut requests: Vec<_> = queue.iter().collect();
+        requests.reverse();
+        for (request, responder) in requests {
+            assert!(!responder.is_abandoned());
+            responder.respond(format!("reply {request}"));
+        }
+
+        for (i, reply) in replies.iter().enumerate() {
+            assert!(reply.is_done());
+            assert_eq!(reply.try_take(), Ok(Some(format!("reply {}", i + 1))));
+            assert_eq!(reply.try_take(), Ok(None));
+        }
+    }
+
+    #[test]
+    fn dropped_responder_is_unanswered() {
+        let queue = RequestQueue::<u32, u32>::default();
+        let mut reply = queue.push_request(1);
+        let flag = Arc::new(Flag::default());
+        let waker = Waker::from(flag.clone());
+        let mut cx = Context::from_waker(&waker);
+        assert!(Pin::new(&mut reply).poll(&mut cx).is_pending());
+        assert_eq!(reply.try_take(), Ok(None));
+
+        drop(queue.pop());
+
+        assert!(flag.0.load(Ordering::SeqCst));
+        assert!(reply.is_done());
+        assert_eq!(reply.try_take(), Err(Unanswered));
+        assert_eq!(
+            Pin::new(&mut reply).poll(&mut cx),
+            Poll::Ready(Err(Unanswered))
+        );
+    }
+
+    #[test]
+    fn abandoned_reply() {
+        let queue = RequestQueue::<u32, u32>::default();
+        drop(queue.push_request(1));
+        let (_, responder) = queue.pop().unwrap();
+        assert!(responder.is_abandoned());
+        responder.respond(2);
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::{BTreeMap, HashMap};
+
+use bevy_ecs::{entity::Entities, prelude::*, system::SystemParam};
+
+/// An energy-based turn scheduler.
+///
+/// Entities are kept in order of the time, in ticks, of their next action. The
+/// entity with the earliest time is the one whose turn it is, and the current
+/// time advances to its time. Entities scheduled for the same time act in the
+/// order they were scheduled, so turn order is deterministic.
+#[derive(Resource, Default)]
+pub struct TurnScheduler {
+    now: u64,
+    next_seq: u64,
+    queue: BTreeMap<(u64, u64), Entity>,
+    scheduled: HashMap<Entity, (u64, u64)>,
+}
+
+impl TurnScheduler {
+    /// Create a new scheduler.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The current time: the time of the current turn, or the time of the
+    /// last turn if nothing is scheduled.
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    /// The entity whose turn it is. Returns `None` if nothing is scheduled.
+    pub fn current(&self) -> Option<Entity> {
+        self.queue.first_key_value().map(|(_, entity)| *entity)
+    }
+
+    /// Test if the given entity is scheduled.
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.scheduled.contains_key(&entity)
+    }
+
+    /// The time of the given entity's next action, if it is scheduled.
+    pub fn time_of(&self, entity: Entity) -> Option<u64> {
+        self.scheduled.get(&entity).map(|(time, _)| *time)
+    }
+
+    /// The number of scheduled entities.
+    pub fn len(&self) -> usize {
+        self.scheduled.len()
+    }
+
+    /// Test if no entities are scheduled.
+    pub fn is_empty(&self) -> bool {
+        self.scheduled.is_empty()
+    }
+
+    /// Schedule the entity to act `delay` ticks from now. If the entity is
+    /// already scheduled, it is rescheduled.
+    pub fn schedule(&mut self, entity: Entity, delay: u64) {
+        self.schedule_at(entity, self.now.saturating_add(delay));
+    }
+
+    /// Schedule the entity to act at the given time. Times in the past are
+    /// treated as now. If the entity is already scheduled, it is rescheduled.
+    pub fn schedule_at(&mut self, entity: Entity, time: u64) {
+        self.unschedule(entity);
+
+        let key = (time.max(self.now), self.next_seq);
+        self.next_seq += 1;
+        self.queue.insert(key, entity);
+        self.scheduled.insert(entity, key);
+        self.advance();
+    }
+
+    /// End the current entity's turn, rescheduling it to act again `cost`
+    /// ticks from now. Returns the entity whose turn ended, or `None` if
+    /// nothing is scheduled.
+    pub fn end_turn(&mut self, cost: u64) -> Option<Entity> {
+        let entity = self.current()?;
+        self.schedule(entity, cost);
+        Some(entity)
+    }
+
+    /// Remove the entity from the scheduler. Returns `true` if it was
+    /// scheduled.
+    pub fn remove(&mut self, entity: Entity) -> bool {
+        let removed = self.unschedule(entity);
+        self.advance();
+        removed
+    }
+
+    /// Remove every entity for which the predicate returns `false`.
+    pub fn retain(&mut self, mut f: impl FnMut(Entity) -> bool) {
+        let removed: Vec<_> = self
+            .scheduled
+            .keys()
+            .copied()
+            .filter(|entity| !f(*entity))
+            .collect();
+        for entity in removed {
+            self.unschedule(entity);
+        }
+        self.advance();
+    }
+
+    fn unschedule(&mut self, entity: Entity) -> bool {
+        match self.scheduled.remove(&entity) {
+            Some(key) => {
+                self.queue.remove(&key);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Move the current time forward to the time of the current turn.
+    fn advance(&mut self) {
+        if let Some(((time, _), _)) = self.queue.first_key_value() {
+            self.now = self.now.max(*time);
+        }
+    }
+}
+
+/// Find out whose turn it is.
+///
+/// Used as a convenience `SystemParam` rather than operating on the underlying
+/// [`TurnScheduler`] directly.
+#[derive(SystemParam)]
+pub struct CurrentTurn<'w> {
+    scheduler: Res<'w, TurnScheduler>,
+}
+
+impl<'w> CurrentTurn<'w> {
+    /// The entity whose turn it is.
+    pub fn entity(&self) -> Option<Entity> {
+        self.scheduler.current()
+    }
+
+    /// Test if it is the given entity's turn.
+    pub fn is(&self, entity: Entity) -> bool {
+        self.entity() == Some(entity)
+    }
+
+    /// The current time.
+    pub fn now(&self) -> u64 {
+        self.scheduler.now()
+    }
+}
+
+/// Run condition that is true when it is the turn of an entity with the
+/// component `C`.
+///
+/// For example, `.run_if(turn_of::<Player>())` only runs a system on the
+/// player's turn.
+pub fn turn_of<C: Component>() -> impl FnMut(CurrentTurn, Query<(), With<C>>) -> bool + Clone {
+    |turn: CurrentTurn, actors: Query<(), With<C>>| {
+        turn.entity().is_some_and(|entity| actors.contains(entity))
+    }
+}
+
+/// System that removes despawned entities from the [`TurnScheduler`].
+pub fn remove_despawned_actors(mut scheduler: ResMut<TurnScheduler>, entities: &Entities) {
+    if scheduler
+        .scheduled
+        .keys()
+        .any(|entity| !entities.contains(*entity))
+    {
+        scheduler.retain(|entity| entities.contains(entity));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entities(n: u32) -> Vec<Entity> {
+        (0..n).map(Entity::from_raw).collect()
+    }
+
+    /// Take turns until nothing is scheduled, returning who acted and when.
+    fn take_turns(scheduler: &mut TurnScheduler) -> Vec<(Entity, u64)> {
+        let mut turns = Vec::new();
+        while let Some(entity) = scheduler.current() {
+            turns.push((entity, scheduler.now()));
+            scheduler.remove(entity);
+        }
+        turns
+    }
+
+    #[test]
+    fn ties_act_in_scheduling_order() {
+        let e = entities(4);
+        let mut scheduler = TurnScheduler::new();
+        scheduler.schedule_at(e[3], 5);
+        scheduler.schedule_at(e[2], 10);
+        scheduler.schedule_at(e[0], 10);
+        scheduler.schedule_at(e[1], 10);
+
+        assert_eq!(
+            take_turns(&mut scheduler),
+            [(e[3], 5), (e[2], 10), (e[0], 10), (e[1], 10)]
+        );
+    }
+
+    #[test]
+    fn rescheduled_ties_go_last() {
+        let e = entities(3);
+        let mut scheduler = TurnScheduler::new();
+        for entity in &e {
+            scheduler.schedule_at(*entity, 0);
+        }
+        assert_eq!(scheduler.end_turn(0), Some(e[0]));
+
+        assert_eq!(
+            take_turns(&mut scheduler),
+            [(e[1], 0), (e[2], 0), (e[0], 0)]
+        );
+    }
+
+    #[test]
+    fn retain_calls_predicate_once_per_entity() {
+        let e = entities(4);
+        let mut scheduler = TurnScheduler::new();
+        for (delay, entity) in e.iter().enumerate() {
+            scheduler.schedule_at(*entity, delay as u64);
+        }
+
+        let mut calls = 0;
+        scheduler.retain(|entity| {
+            calls += 1;
+            entity.index() % 2 == 1
+        });
+
+        assert_eq!(calls, 4);
+        assert_eq!(scheduler.len(), 2);
+        assert!(!scheduler.contains(e[0]));
+        assert_eq!(scheduler.current(), Some(e[1]));
+        assert_eq!(scheduler.now(), 1);
+        assert_eq!(take_turns(&mut scheduler), [(e[1], 1), (e[3], 3)]);
+    }
+}
@@ -0,0 +1,64 @@
+use std::convert::Infallible;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+use bevy_ecs::{prelude::*, schedule::SystemSet};
+
+use crate::Queue;
+
+/// System sets for the systems that push to and pop from the [`Queue`] of
+/// type `T`.
+///
+/// Queues added with the `QueuePlugin` order [`QueueSet::Write`] before
+/// [`QueueSet::Read`] in each of the main schedules, so that items pushed in a
+/// frame are read in the same frame. Add systems to the sets with, for example,
+/// `.in_set(QueueSet::<T>::Write)`.
+#[derive(SystemSet)]
+pub enum QueueSet<T> {
+    /// Systems that push onto the queue.
+    Write,
+    /// Systems that pop from the queue.
+    Read,
+    #[doc(hidden)]
+    _Marker(PhantomData<fn() -> T>, Infallible),
+}
+
+impl<T> Clone for QueueSet<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for QueueSet<T> {}
+
+impl<T> PartialEq for QueueSet<T> {
+    fn eq(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+impl<T> Eq for QueueSet<T> {}
+
+impl<T> Hash for QueueSet<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+    }
+}
+
+impl<T> fmt::Debug for QueueSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let set = match self {
+            QueueSet::Write => "Write",
+            QueueSet::Read => "Read",
+            QueueSet::_Marker(_, never) => match *never {},
+        };
+        write!(f, "QueueSet::<{}>::{}", std::any::type_name::<T>(), set)
+    }
+}
+
+/// Run condition that is true when the [`Queue`] of type `T` exists and is not
+/// empty.
+pub fn queue_not_empty<T: Send + 'static>() -> impl FnMut(Option<Res<Queue<T>>>) -> bool + Clone {
+    |queue: Option<Res<Queue<T>>>| queue.is_some_and(|queue| !queue.is_empty())
+}
@@ -0,0 +1,123 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tracing::{info_span, Span};
+
+static NEXT_MESSAGE_ID: AtomicU64 = AtomicU64::new(0);
+
+/// An item pushed onto a [`Queue`](crate::Queue), with the tracing context of
+/// its push.
+///
+/// Each pushed item gets a `queue_message` span, recording the item's ID, the
+/// queue's type name and the name of the system that pushed it, if it was
+/// pushed through a [`QueueWriter`](crate::QueueWriter). The span is a child of
+/// the span that was current when the item was pushed.
+///
+/// Handling items with [`Queue::iter`](crate::Queue::iter) or
+/// [`Queue::for_each_in_span`](crate::Queue::for_each_in_span) enters each
+/// item's span while the item is being handled, so items pushed while handling
+/// it are children of its span, and the whole chain of messages shows up in log
+/// output and profilers. [`Queue::pop`](crate::Queue::pop) only enters the span
+/// to record that the item was popped.
+pub struct Traced<T> {
+    id: u64,
+    span: Span,
+    value: T,
+}
+
+impl<T> Traced<T> {
+    pub(crate) fn new(value: T, system: Option<&str>) -> Self {
+        let id = NEXT_MESSAGE_ID.fetch_add(1, Ordering::Relaxed);
+        let span = info_span!(
+            "queue_message",
+            id,
+            queue = std::any::type_name::<T>(),
+            system
+        );
+        Self { id, span, value }
+    }
+
+    /// The ID of the item, unique within the process.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// The span created when the item was pushed.
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+
+    /// The item itself.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Take the item, dropping its tracing context.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Take the item and the span created when it was pushed.
+    pub fn into_parts(self) -> (T, Span) {
+        (self.value, self.span)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing::Span;
+
+    use crate::{Queue, QueueIter};
+
+    #[test]
+    fn iterator_is_send() {
+        fn assert_send<S: Send>() {}
+        assert_send::<QueueIter<'static, u32>>();
+    }
+
+    #[test]
+    fn handles_items_inside_their_spans() {
+        tracing::subscriber::with_default(tracing_subscriber::registry(), || {
+            let queue = Queue::new();
+            queue.push(1);
+            queue.push(2);
+
+            let mut handled = Vec::new();
+            queue.for_each_in_span(|value| {
+                let span = Span::current();
+                handled.push((value, span.metadata().map(|metadata| metadata.name())));
+            });
+            assert_eq!(
+                handled,
+                [(1, Some("queue_message")), (2, Some("queue_message"))]
+            );
+            assert!(Span::current().is_none());
+        });
+    }
+
+    #[test]
+    fn iterator_enters_each_span() {
+        tracing::subscriber::with_default(tracing_subscriber::registry(), || {
+            let queue = Queue::new();
+            for i in 0..3 {
+                queue.push(i);
+            }
+
+            let mut handled = Vec::new();
+            for value in queue.iter() {
+                let span = Span::current();
+                handled.push((value, span.metadata().map(|metadata| metadata.name())));
+                if value == 1 {
+                    break;
+                }
+            }
+            assert_eq!(
+                handled,
+                [(0, Some("queue_message")), (1, Some("queue_message"))]
+            );
+            assert!(Span::current().is_none());
+
+            assert_eq!(queue.pop(), Some(2));
+            assert!(Span::current().is_none());
+        });
+    }
+}
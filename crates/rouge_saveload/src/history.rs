@@ -0,0 +1,125 @@
+use std::cmp::Reverse;
+use std::marker::PhantomData;
+
+use bevy::prelude::Resource;
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::{instrument, warn};
+
+use crate::sys::{append_line, read_text};
+use crate::SaveloadError as Error;
+
+/// Append-only store of records about past runs, such as a hall of fame or
+/// morgue entries.
+///
+/// Records are stored separately from saves, one per line, so they outlive the
+/// save when it is deleted. The record type `T` is up to the game, e.g. score,
+/// cause of death, depth, seed and timestamp.
+#[derive(Resource)]
+pub struct RunHistory<T> {
+    filename: String,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> RunHistory<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    /// Create a run history stored in the file at the given `filename`.
+    pub fn new(filename: impl Into<String>) -> Self {
+        Self {
+            filename: filename.into(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Append a record to the history.
+    #[instrument(skip_all, fields(filename = self.filename))]
+    pub fn append(&self, record: &T) -> Result<(), Error> {
+        let line = ron::to_string(record).map_err(|source| Error::Serialize(source.into()))?;
+        append_line(&self.filename, &line)
+    }
+
+    /// Read every record in the history, oldest first.
+    ///
+    /// Records that fail to deserialize, such as a line left incomplete by a
+    /// crash, are skipped.
+    #[instrument(skip_all, fields(filename = self.filename))]
+    pub fn records(&self) -> Result<Vec<T>, Error> {
+        let Some(text) = read_text(&self.filename)? else {
+            return Ok(Vec::new());
+        };
+        Ok(text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match ron::from_str(line) {
+                Ok(record) => Some(record),
+                Err(e) => {
+                    warn!("Skipping unreadable run history record: {:?}", e);
+                    None
+                }
+            })
+            .collect())
+    }
+
+    /// Return the `n` records with the highest `key`, highest first. Records
+    /// with equal keys are kept in the order they were appended.
+    pub fn top_by_key<K, F>(&self, n: usize, mut key: F) -> Result<Vec<T>, Error>
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        let mut records = self.records()?;
+        records.sort_by_key(|record| Reverse(key(record)));
+        records.truncate(n);
+        Ok(records)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sys::{delete_save, write_text};
+
+    #[test]
+    fn appends_and_ranks_records() {
+        let filename = "history_test_ranks.ron";
+        let history = RunHistory::<(String, u32)>::new(filename);
+        for (name, score) in [("a", 10), ("b", 30), ("c", 20), ("d", 30)] {
+            history.append(&(name.to_string(), score)).unwrap();
+        }
+
+        let reloaded = RunHistory::<(String, u32)>::new(filename);
+        let names = |records: Vec<(String, u32)>| {
+            records
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+        };
+        let records = reloaded.records();
+        let top = reloaded.top_by_key(3, |(_, score)| *score);
+        delete_save(filename).unwrap();
+
+        assert_eq!(names(records.unwrap()), ["a", "b", "c", "d"]);
+        assert_eq!(names(top.unwrap()), ["b", "d", "c"]);
+    }
+
+    #[test]
+    fn skips_malformed_lines() {
+        let filename = "history_test_malformed.ron";
+        write_text(filename, "(\"a\",1)\n(\"b\",\n\n(\"c\",3)\n").unwrap();
+
+        let records = RunHistory::<(String, u32)>::new(filename).records();
+        delete_save(filename).unwrap();
+
+        assert_eq!(
+            records.unwrap(),
+            [("a".to_string(), 1), ("c".to_string(), 3)]
+        );
+    }
+
+    #[test]
+    fn missing_history_is_empty() {
+        let history = RunHistory::<(String, u32)>::new("history_test_missing.ron");
+        assert!(history.records().unwrap().is_empty());
+    }
+}
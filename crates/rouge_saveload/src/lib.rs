@@ -3,12 +3,22 @@ mod sys;
 pub use plugin::*;
 pub use sys::*;
 mod compress;
-#[cfg(target_arch = "wasm32")]
 mod encode;
+mod history;
+pub use history::*;
+#[cfg(feature = "queue")]
+mod queue;
+#[cfg(feature = "queue")]
+pub use queue::*;
 mod serialize;
+mod settings;
+pub use settings::*;
+mod share;
+pub use share::*;
+pub mod testing;
 
-#[cfg(target_arch = "wasm32")]
 use base64::DecodeError;
+use bevy::scene::SceneSpawnError;
 use thiserror::Error;
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::JsValue;
@@ -22,10 +32,27 @@ pub enum SaveloadError {
     #[error("Failed to deserialize save data")]
     Deserialize(#[source] anyhow::Error),
 
-    #[cfg(target_arch = "wasm32")]
     #[error("Failed to decode save data")]
     Decode(#[from] DecodeError),
 
+    #[error("No save found in slot `{slot}`")]
+    NoSave { slot: String },
+
+    #[error("Share code is not a valid save")]
+    InvalidShareCode,
+
+    #[error("Share code version {version} is not supported")]
+    UnsupportedVersion { version: u8 },
+
+    #[error("Share code was exported with a different save format")]
+    FormatMismatch,
+
+    #[error("Share code checksum mismatch: expected {expected:#010x}, found {found:#010x}")]
+    ChecksumMismatch { expected: u32, found: u32 },
+
+    #[error("Failed to write scene to world")]
+    Spawn(#[from] SceneSpawnError),
+
     #[error("IO error occurred")]
     IO(#[from] std::io::Error),
 
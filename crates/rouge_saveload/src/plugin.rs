@@ -1,74 +1,213 @@
 use bevy::ecs::entity::EntityHashMap;
+use bevy::ecs::system::Command;
 use bevy::prelude::*;
 use bevy::scene::DynamicScene;
+use tracing::{error, warn};
 
 use crate::{load_scene, save_scene};
 
-/// Flag resource that indicates that the game should be saved.
-///
-/// To save the game, insert a `SaveLoadState` resource with the value
-/// [`SaveLoadState::Save`]. To load a save, insert the value
-/// [`SaveLoadState::Load`].
-#[derive(Resource)]
-pub enum SaveLoadState {
-    Save,
-    Load,
+type HookFn = dyn Fn(&mut World) + Send + Sync;
+
+/// Functions run around saves and loads, such as to move state that isn't
+/// reflected into the `World` before saving.
+#[derive(Resource, Default)]
+pub(crate) struct SaveloadHooks {
+    pub(crate) before_save: Vec<Box<HookFn>>,
+    pub(crate) after_save: Vec<Box<HookFn>>,
+    pub(crate) after_load: Vec<Box<HookFn>>,
 }
 
-fn save(world: &mut World) {
-    if matches!(
-        world.get_resource::<SaveLoadState>(),
-        Some(SaveLoadState::Save)
-    ) {
-        let type_registry = world.resource::<AppTypeRegistry>();
-        let scene = DynamicScene::from_world(world);
+fn run_hooks(world: &mut World, hooks: impl Fn(&SaveloadHooks) -> &[Box<HookFn>]) {
+    if !world.contains_resource::<SaveloadHooks>() {
+        return;
+    }
+    world.resource_scope(|world, all: Mut<SaveloadHooks>| {
+        for hook in hooks(&all) {
+            hook(world);
+        }
+    });
+}
 
-        save_scene("savegame.scn", scene, type_registry).expect("Failed to save game!");
+fn save(world: &mut World, slot: &str) {
+    run_hooks(world, |hooks| &hooks.before_save);
+    let scene = DynamicScene::from_world(world);
+    run_hooks(world, |hooks| &hooks.after_save);
 
-        world.remove_resource::<SaveLoadState>();
+    let type_registry = world.resource::<AppTypeRegistry>();
+    if let Err(e) = save_scene(slot, scene, type_registry) {
+        error!("Failed to save game to slot `{}`: {:?}", slot, e);
     }
 }
 
-fn load(world: &mut World) {
-    if matches!(
-        world.get_resource::<SaveLoadState>(),
-        Some(SaveLoadState::Load)
-    ) {
-        world.clear_entities();
-        world.clear_trackers();
-
-        let scene = {
-            let type_registry = world.get_resource::<AppTypeRegistry>().unwrap();
-            if let Some(scene) =
-                load_scene("savegame.scn", type_registry).expect("Failed to load game!")
-            {
-                scene
-            } else {
+/// Load the game from `slot`, leaving the `World` untouched if the save is
+/// missing or can't be read.
+fn load(world: &mut World, slot: &str) {
+    let scene = {
+        let type_registry = world.resource::<AppTypeRegistry>();
+        match load_scene(slot, type_registry) {
+            Ok(Some(scene)) => scene,
+            Ok(None) => {
+                warn!("No save found in slot `{}`", slot);
                 return;
             }
-        };
+            Err(e) => {
+                error!("Failed to load game from slot `{}`: {:?}", slot, e);
+                return;
+            }
+        }
+    };
+
+    world.clear_entities();
+    world.clear_trackers();
+
+    let mut entity_map = EntityHashMap::default();
+    if let Err(e) = scene.write_to_world(world, &mut entity_map) {
+        error!("Failed to write loaded game to the world: {:?}", e);
+        return;
+    }
+    run_hooks(world, |hooks| &hooks.after_load);
+}
 
-        let mut entity_map = EntityHashMap::default();
+/// [`Command`] that saves the game to the given slot.
+///
+/// Usually queued with [`SaveloadCommandsExt::save_game`].
+pub struct SaveGame {
+    pub slot: String,
+}
 
-        scene
-            .write_to_world(world, &mut entity_map)
-            .expect("Failed to write scene!");
+impl Command for SaveGame {
+    fn apply(self, world: &mut World) {
+        save(world, &self.slot);
+    }
+}
 
-        world.remove_resource::<SaveLoadState>();
+/// [`Command`] that loads the game from the given slot, replacing all existing
+/// entities.
+///
+/// Usually queued with [`SaveloadCommandsExt::load_game`].
+pub struct LoadGame {
+    pub slot: String,
+}
+
+impl Command for LoadGame {
+    fn apply(self, world: &mut World) {
+        load(world, &self.slot);
     }
 }
 
+/// Extension trait for queueing saves and loads with [`Commands`].
+pub trait SaveloadCommandsExt {
+    /// Save the game to the given slot when commands are next applied.
+    fn save_game(&mut self, slot: impl Into<String>);
+
+    /// Load the game from the given slot when commands are next applied.
+    fn load_game(&mut self, slot: impl Into<String>);
+}
+
+impl SaveloadCommandsExt for Commands<'_, '_> {
+    fn save_game(&mut self, slot: impl Into<String>) {
+        self.add(SaveGame { slot: slot.into() });
+    }
+
+    fn load_game(&mut self, slot: impl Into<String>) {
+        self.add(LoadGame { slot: slot.into() });
+    }
+}
+
+type SaveloadFn = dyn Fn(&mut App) + Send + Sync;
+
 /// Plugin that implements a save/load system.
 ///
-/// To save the game, insert a [`SaveLoadState`] resource with the value
-/// [`SaveLoadState::Save`]. To load a save, insert the value
-/// [`SaveLoadState::Load`].
+/// To save or load the game, use [`SaveloadCommandsExt::save_game`] or
+/// [`SaveloadCommandsExt::load_game`]. Saves and loads can also be tied to
+/// states with the [`SaveloadPlugin::save_on_enter`] and
+/// [`SaveloadPlugin::load_on_enter`] builders.
 #[derive(Default)]
-pub struct SaveloadPlugin {}
+pub struct SaveloadPlugin {
+    systems: Vec<Box<SaveloadFn>>,
+}
+
+impl SaveloadPlugin {
+    /// Save the game to `slot` when entering `state`, then transition to
+    /// `next`.
+    pub fn save_on_enter<S: States>(mut self, state: S, next: S, slot: impl Into<String>) -> Self {
+        let slot = slot.into();
+        self.systems.push(Box::new(move |app: &mut App| {
+            let (slot, next) = (slot.clone(), next.clone());
+            app.add_systems(OnEnter(state.clone()), move |world: &mut World| {
+                save(world, &slot);
+                world.resource_mut::<NextState<S>>().set(next.clone());
+            });
+        }));
+        self
+    }
+
+    /// Save the pending items of the [`Queue`](rouge_queue::Queue) of type `T`
+    /// with the game, as a [`PersistedQueue`](crate::PersistedQueue)
+    /// resource.
+    ///
+    /// Loading a save replaces the queue's items with the saved ones, in the
+    /// same order. Items pushed with a delay that isn't yet due are not saved,
+    /// and entities referenced by items are not mapped to their loaded
+    /// counterparts.
+    #[cfg(feature = "queue")]
+    pub fn persist_queue<T>(mut self) -> Self
+    where
+        T: FromReflect + TypePath + bevy::reflect::GetTypeRegistration,
+    {
+        self.systems
+            .push(Box::new(crate::queue::persist_reflect_queue::<T>));
+        self
+    }
+
+    /// Save the pending items of the [`Queue`](rouge_queue::Queue) of type `T`
+    /// with the game, serialized with serde. Like
+    /// [`SaveloadPlugin::persist_queue`], but for types that implement
+    /// `Serialize` rather than `Reflect`.
+    #[cfg(feature = "queue")]
+    pub fn persist_serialized_queue<T>(mut self) -> Self
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned + Send + 'static,
+    {
+        self.systems
+            .push(Box::new(crate::queue::persist_serialized_queue::<T>));
+        self
+    }
+
+    /// Load the game from `slot` when entering `state`, then transition to
+    /// `next`.
+    pub fn load_on_enter<S: States>(mut self, state: S, next: S, slot: impl Into<String>) -> Self {
+        let slot = slot.into();
+        self.systems.push(Box::new(move |app: &mut App| {
+            let (slot, next) = (slot.clone(), next.clone());
+            app.add_systems(OnEnter(state.clone()), move |world: &mut World| {
+                load(world, &slot);
+                world.resource_mut::<NextState<S>>().set(next.clone());
+            });
+        }));
+        self
+    }
+}
 
 impl Plugin for SaveloadPlugin {
     fn build(&self, app: &mut bevy::app::App) {
-        app.add_systems(PostUpdate, save);
-        app.add_systems(PreUpdate, load);
+        for system in self.systems.iter() {
+            system(app);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_save_keeps_world() {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        let entity = world.spawn_empty().id();
+
+        load(&mut world, "rouge_saveload_missing_slot.scn");
+        assert!(world.get_entity(entity).is_some());
     }
 }
@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy::reflect::{GetTypeRegistration, TypePath};
+use rouge_queue::Queue;
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::{error, warn};
+
+use crate::plugin::SaveloadHooks;
+
+/// Resource holding the pending items of a [`Queue`] of type `T` while the
+/// game is saved, so that they are written to the save as part of the scene.
+///
+/// Added by [`SaveloadPlugin::persist_queue`](crate::SaveloadPlugin::persist_queue),
+/// and removed again once the save has been made or loaded.
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct PersistedQueue<T: FromReflect + TypePath> {
+    pub items: Vec<T>,
+}
+
+impl<T: FromReflect + TypePath> Default for PersistedQueue<T> {
+    fn default() -> Self {
+        Self { items: Vec::new() }
+    }
+}
+
+/// Resource holding the pending items of the queues persisted with
+/// [`SaveloadPlugin::persist_serialized_queue`](crate::SaveloadPlugin::persist_serialized_queue)
+/// while the game is saved, as ron strings keyed by the queue's type name.
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct PersistedSerializedQueues {
+    pub queues: HashMap<String, Vec<String>>,
+}
+
+/// Call `f` with every pending item in the queue, leaving them in place.
+fn for_each_pending<T: Send + 'static>(world: &World, f: impl FnMut(&T)) {
+    if let Some(queue) = world.get_resource::<Queue<T>>() {
+        queue.for_each_pending(f);
+    }
+}
+
+/// Replace the contents of the queue with the given items, in order.
+fn restore<T: Send + 'static>(world: &mut World, items: impl IntoIterator<Item = T>) {
+    world
+        .get_resource_or_insert_with(Queue::<T>::default)
+        .restore(items);
+}
+
+pub(crate) fn persist_reflect_queue<T>(app: &mut App)
+where
+    T: FromReflect + TypePath + GetTypeRegistration,
+{
+    app.register_type::<T>()
+        .register_type::<PersistedQueue<T>>()
+        .register_type::<Vec<T>>();
+    let mut hooks = app
+        .world
+        .get_resource_or_insert_with(SaveloadHooks::default);
+    hooks.before_save.push(Box::new(|world: &mut World| {
+        let mut items = Vec::new();
+        for_each_pending::<T>(world, |item| match T::from_reflect(item) {
+            Some(item) => items.push(item),
+            None => error!("Failed to copy queued item"),
+        });
+        world.insert_resource(PersistedQueue { items });
+    }));
+    hooks.after_save.push(Box::new(|world: &mut World| {
+        world.remove_resource::<PersistedQueue<T>>();
+    }));
+    hooks.after_load.push(Box::new(|world: &mut World| {
+        let items = world
+            .remove_resource::<PersistedQueue<T>>()
+            .map(|persisted| persisted.items)
+            .unwrap_or_default();
+        restore(world, items);
+    }));
+}
+
+pub(crate) fn persist_serialized_queue<T>(app: &mut App)
+where
+    T: Serialize + DeserializeOwned + Send + 'static,
+{
+    app.register_type::<PersistedSerializedQueues>()
+        .register_type::<HashMap<String, Vec<String>>>()
+        .register_type::<Vec<String>>();
+    let mut hooks = app
+        .world
+        .get_resource_or_insert_with(SaveloadHooks::default);
+    hooks.before_save.push(Box::new(|world: &mut World| {
+        let mut serialized = Vec::new();
+        for_each_pending::<T>(world, |item| match ron::to_string(item) {
+            Ok(item) => serialized.push(item),
+            Err(e) => error!("Failed to serialize queued item: {:?}", e),
+        });
+        world
+            .get_resource_or_insert_with(PersistedSerializedQueues::default)
+            .queues
+            .insert(std::any::type_name::<T>().to_string(), serialized);
+    }));
+    hooks.after_save.push(Box::new(|world: &mut World| {
+        world.remove_resource::<PersistedSerializedQueues>();
+    }));
+    hooks.after_load.push(Box::new(|world: &mut World| {
+        let serialized = world
+            .get_resource_mut::<PersistedSerializedQueues>()
+            .and_then(|mut persisted| persisted.queues.remove(std::any::type_name::<T>()))
+            .unwrap_or_default();
+        let items = serialized
+            .iter()
+            .filter_map(|item| match ron::from_str::<T>(item) {
+                Ok(item) => Some(item),
+                Err(e) => {
+                    warn!("Skipping unreadable queued item: {:?}", e);
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        restore(world, items);
+        if world
+            .get_resource::<PersistedSerializedQueues>()
+            .is_some_and(|persisted| persisted.queues.is_empty())
+        {
+            world.remove_resource::<PersistedSerializedQueues>();
+        }
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use bevy::ecs::system::Command;
+    use rouge_queue::{EventBridgeDirection, QueuePlugin, QueueRecorderPlugin};
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::{delete_save, LoadGame, SaveGame, SaveloadPlugin};
+
+    #[derive(Event, Reflect, Serialize, Deserialize, Clone, Debug, PartialEq)]
+    struct Msg(u32);
+
+    #[derive(Resource, Default)]
+    struct Seen(Vec<u32>);
+
+    fn recording(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "rouge_saveload_{}_{}.ron",
+            name,
+            std::process::id()
+        ))
+    }
+
+    fn app(recorder: QueueRecorderPlugin, saveload: SaveloadPlugin) -> App {
+        let mut app = App::new();
+        app.add_plugins(
+            QueuePlugin::default().bridge_events::<Msg>(EventBridgeDirection::Both, Update),
+        )
+        .add_plugins(recorder.with_queue::<Msg>())
+        .add_plugins(saveload)
+        .init_resource::<Seen>()
+        .add_systems(
+            Last,
+            |mut events: EventReader<Msg>, mut seen: ResMut<Seen>| {
+                seen.0.extend(events.read().map(|Msg(i)| i));
+            },
+        );
+        app
+    }
+
+    fn queued(app: &App) -> Vec<Msg> {
+        let mut items = Vec::new();
+        app.world
+            .resource::<Queue<Msg>>()
+            .for_each_pending(|item| items.push(item.clone()));
+        items
+    }
+
+    /// Save the queue, change it, then load it again, checking that saving
+    /// and loading neither record nor forward the items again.
+    fn round_trip(slot: &str, saveload: SaveloadPlugin) {
+        let path = recording(slot);
+        let mut app = app(QueueRecorderPlugin::record(&path), saveload);
+        let queue = app.world.resource::<Queue<Msg>>();
+        queue.push(Msg(1));
+        queue.push(Msg(2));
+
+        SaveGame { slot: slot.into() }.apply(&mut app.world);
+        assert_eq!(queued(&app), [Msg(1), Msg(2)]);
+        let queue = app.world.resource::<Queue<Msg>>();
+        assert_eq!(queue.pop(), Some(Msg(1)));
+        queue.push(Msg(3));
+        LoadGame { slot: slot.into() }.apply(&mut app.world);
+        app.update();
+
+        let recorded = fs::read_to_string(&path).unwrap().lines().count();
+        fs::remove_file(&path).unwrap();
+        delete_save(slot).unwrap();
+
+        assert_eq!(queued(&app), [Msg(1), Msg(2)]);
+        assert_eq!(app.world.resource::<Seen>().0, [1, 2, 3]);
+        assert_eq!(recorded, 3);
+    }
+
+    #[test]
+    fn reflected_queue_round_trip() {
+        round_trip(
+            "queue_test_reflected.scn",
+            SaveloadPlugin::default().persist_queue::<Msg>(),
+        );
+    }
+
+    #[test]
+    fn serialized_queue_round_trip() {
+        round_trip(
+            "queue_test_serialized.scn",
+            SaveloadPlugin::default().persist_serialized_queue::<Msg>(),
+        );
+    }
+
+    #[test]
+    fn replay_keeps_saved_items() {
+        let slot = "queue_test_replay.scn";
+        let path = recording(slot);
+        fs::write(&path, "").unwrap();
+        let mut app = app(
+            QueueRecorderPlugin::replay(&path),
+            SaveloadPlugin::default().persist_serialized_queue::<Msg>(),
+        );
+        let queue = app.world.resource::<Queue<Msg>>();
+        queue.restore([Msg(1), Msg(2)]);
+        queue.push(Msg(3));
+
+        SaveGame { slot: slot.into() }.apply(&mut app.world);
+        let saved = queued(&app);
+        app.world.resource::<Queue<Msg>>().restore([]);
+        LoadGame { slot: slot.into() }.apply(&mut app.world);
+        let loaded = queued(&app);
+        fs::remove_file(&path).unwrap();
+        delete_save(slot).unwrap();
+
+        assert_eq!(saved, [Msg(1), Msg(2)]);
+        assert_eq!(loaded, [Msg(1), Msg(2)]);
+    }
+}
@@ -9,39 +9,65 @@ use serde::de::DeserializeSeed;
 
 use crate::SaveloadError as Error;
 
-/// Serialize a bevy `DynamicScene` to a `Vec<u8>`.
-#[cfg(not(feature = "serialize-binary"))]
-pub fn serialize(scene: DynamicScene, type_registry: &AppTypeRegistry) -> Vec<u8> {
+/// Serialize a bevy `DynamicScene` to a `Vec<u8>` using the enabled save format.
+pub fn serialize(scene: DynamicScene, type_registry: &AppTypeRegistry) -> Result<Vec<u8>, Error> {
+    #[cfg(feature = "serialize-binary")]
+    return serialize_binary(&scene, type_registry);
+    #[cfg(not(feature = "serialize-binary"))]
+    return serialize_ron(&scene, type_registry);
+}
+
+/// Deserialize a bevy `DynamicScene` from a byte slice using the enabled save
+/// format.
+pub fn deserialize(bytes: &[u8], type_registry: &AppTypeRegistry) -> Result<DynamicScene, Error> {
+    #[cfg(feature = "serialize-binary")]
+    return deserialize_binary(bytes, type_registry);
+    #[cfg(not(feature = "serialize-binary"))]
+    return deserialize_ron(bytes, type_registry);
+}
+
+/// Serialize a bevy `DynamicScene` to ron text.
+pub fn serialize_ron(
+    scene: &DynamicScene,
+    type_registry: &AppTypeRegistry,
+) -> Result<Vec<u8>, Error> {
     scene
         .serialize_ron(type_registry)
-        .unwrap()
-        .as_bytes()
-        .to_owned()
+        .map(String::into_bytes)
+        .map_err(|source| Error::Serialize(source.into()))
 }
 
-/// Deserialize a bevy `DynamicScene` from a byte slice.
-#[cfg(not(feature = "serialize-binary"))]
-pub fn deserialize(bytes: &[u8], type_registry: &AppTypeRegistry) -> DynamicScene {
+/// Deserialize a bevy `DynamicScene` from ron text.
+pub fn deserialize_ron(
+    bytes: &[u8],
+    type_registry: &AppTypeRegistry,
+) -> Result<DynamicScene, Error> {
     let scene_deserializer = SceneDeserializer {
         type_registry: &type_registry.0.write(),
     };
-    let mut deserializer =
-        ron::de::Deserializer::from_bytes(bytes).expect("Failed to create deserializer!");
+    let mut deserializer = ron::de::Deserializer::from_bytes(bytes)
+        .map_err(|source| Error::Deserialize(source.into()))?;
     scene_deserializer
         .deserialize(&mut deserializer)
-        .expect("Failed to deserialize scene!")
+        .map_err(|source| Error::Deserialize(source.into()))
 }
 
-/// Serialize a bevy `DynamicScene` to a `Vec<u8>`.
+/// Serialize a bevy `DynamicScene` to bincode.
 #[cfg(feature = "serialize-binary")]
-pub fn serialize(scene: DynamicScene, type_registry: &AppTypeRegistry) -> Result<Vec<u8>, Error> {
-    let serializer = SceneSerializer::new(&scene, type_registry);
+pub fn serialize_binary(
+    scene: &DynamicScene,
+    type_registry: &AppTypeRegistry,
+) -> Result<Vec<u8>, Error> {
+    let serializer = SceneSerializer::new(scene, type_registry);
     bincode::serialize(&serializer).map_err(|source| Error::Serialize(source.into()))
 }
 
-/// Deserialize a bevy `DynamicScene` from a byte slice.
+/// Deserialize a bevy `DynamicScene` from bincode.
 #[cfg(feature = "serialize-binary")]
-pub fn deserialize(bytes: &[u8], type_registry: &AppTypeRegistry) -> Result<DynamicScene, Error> {
+pub fn deserialize_binary(
+    bytes: &[u8],
+    type_registry: &AppTypeRegistry,
+) -> Result<DynamicScene, Error> {
     let scene_deserializer = SceneDeserializer {
         type_registry: &type_registry.0.write(),
     };
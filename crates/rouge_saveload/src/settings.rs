@@ -0,0 +1,258 @@
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::{error, instrument, warn};
+
+use crate::sys::{read_text, write_text};
+use crate::SaveloadError as Error;
+
+/// Plugin that persists a settings resource of type `T`, such as key bindings
+/// or volume, separately from game saves.
+///
+/// The settings are loaded when the plugin is built, falling back to
+/// `T::default()` if there are no stored settings or they can't be read. Any
+/// change to the resource, including changes made by `Startup` systems, is
+/// written back once it has stopped changing for the debounce period, and any
+/// pending change is written on [`AppExit`]. The debounce period is measured in
+/// real time, so changes made while the game is paused are still written.
+pub struct SettingsPlugin<T> {
+    filename: String,
+    debounce: Duration,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> SettingsPlugin<T> {
+    /// Persist settings in the file at the given `filename`.
+    pub fn new(filename: impl Into<String>) -> Self {
+        Self {
+            filename: filename.into(),
+            debounce: Duration::from_secs(1),
+            marker: PhantomData,
+        }
+    }
+
+    /// Set how long the settings must go unchanged before they are written.
+    /// Defaults to one second.
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+}
+
+impl<T> Plugin for SettingsPlugin<T>
+where
+    T: Serialize + DeserializeOwned + Resource + Default,
+{
+    fn build(&self, app: &mut App) {
+        let settings = match load_settings::<T>(&self.filename) {
+            Ok(Some(settings)) => settings,
+            Ok(None) => T::default(),
+            Err(e) => {
+                warn!("Failed to load settings, using defaults: {:?}", e);
+                T::default()
+            }
+        };
+
+        let saved = to_text(&settings).ok();
+        app.insert_resource(settings)
+            .insert_resource(SettingsState::<T> {
+                filename: self.filename.clone(),
+                debounce: self.debounce,
+                pending: None,
+                saved,
+                marker: PhantomData,
+            })
+            .add_systems(Update, save_changed_settings::<T>)
+            .add_systems(Last, save_settings_on_exit::<T>);
+    }
+}
+
+/// Where settings of type `T` are stored, and when pending changes are due to
+/// be written.
+#[derive(Resource)]
+struct SettingsState<T> {
+    filename: String,
+    debounce: Duration,
+    pending: Option<Duration>,
+    /// The settings as last loaded or written, to skip writes that wouldn't
+    /// change anything.
+    saved: Option<String>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> SettingsState<T>
+where
+    T: Serialize,
+{
+    fn write(&mut self, settings: &T) {
+        self.pending = None;
+        let text = match to_text(settings) {
+            Ok(text) if self.saved.as_ref() == Some(&text) => return,
+            Ok(text) => text,
+            Err(e) => {
+                error!("Failed to save settings: {:?}", e);
+                return;
+            }
+        };
+        match write_text(&self.filename, &text) {
+            Ok(()) => self.saved = Some(text),
+            Err(e) => error!("Failed to save settings: {:?}", e),
+        }
+    }
+}
+
+fn save_changed_settings<T>(
+    settings: Res<T>,
+    mut state: ResMut<SettingsState<T>>,
+    time: Res<Time<Real>>,
+) where
+    T: Serialize + Resource,
+{
+    if settings.is_changed() {
+        state.pending = Some(time.elapsed() + state.debounce);
+    }
+    if state.pending.is_some_and(|due| time.elapsed() >= due) {
+        state.write(&settings);
+    }
+}
+
+fn save_settings_on_exit<T>(
+    settings: Res<T>,
+    mut state: ResMut<SettingsState<T>>,
+    mut exit: EventReader<AppExit>,
+) where
+    T: Serialize + Resource,
+{
+    if exit.read().last().is_some() && state.pending.is_some() {
+        state.write(&settings);
+    }
+}
+
+/// Load settings from the file at the given `filename`. Returns `None` if
+/// there are no stored settings.
+#[instrument]
+pub fn load_settings<T>(filename: &str) -> Result<Option<T>, Error>
+where
+    T: DeserializeOwned,
+{
+    read_text(filename)?
+        .map(|text| ron::from_str(&text).map_err(|source| Error::Deserialize(source.into())))
+        .transpose()
+}
+
+/// Save settings to the file at the given `filename`.
+#[instrument(skip(settings))]
+pub fn save_settings<T>(filename: &str, settings: &T) -> Result<(), Error>
+where
+    T: Serialize,
+{
+    write_text(filename, &to_text(settings)?)
+}
+
+fn to_text<T>(settings: &T) -> Result<String, Error>
+where
+    T: Serialize,
+{
+    ron::ser::to_string_pretty(settings, Default::default())
+        .map_err(|source| Error::Serialize(source.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::sys::delete_save;
+
+    #[derive(Resource, Serialize, Deserialize, Default, Debug, PartialEq)]
+    struct Volume(u32);
+
+    /// Build an app whose real time only moves when `advance` is called.
+    fn app(filename: &str, debounce: Duration) -> (App, Instant) {
+        let start = Instant::now();
+        let mut app = App::new();
+        app.insert_resource(Time::<Real>::new(start))
+            .add_plugins(SettingsPlugin::<Volume>::new(filename).with_debounce(debounce));
+        (app, start)
+    }
+
+    fn advance(app: &mut App, to: Instant) {
+        app.world
+            .resource_mut::<Time<Real>>()
+            .update_with_instant(to);
+        app.update();
+    }
+
+    fn stored(filename: &str) -> Option<Volume> {
+        load_settings(filename).unwrap()
+    }
+
+    #[test]
+    fn writes_changes_after_debounce() {
+        let filename = "settings_test_debounce.ron";
+        let (mut app, start) = app(filename, Duration::from_secs(1));
+        app.update();
+        assert_eq!(stored(filename), None);
+
+        app.world.resource_mut::<Volume>().0 = 3;
+        advance(&mut app, start + Duration::from_millis(100));
+        app.world.resource_mut::<Volume>().0 = 5;
+        advance(&mut app, start + Duration::from_millis(800));
+        let early = stored(filename);
+        advance(&mut app, start + Duration::from_millis(1900));
+        let written = stored(filename);
+        delete_save(filename).unwrap();
+
+        assert_eq!(early, None);
+        assert_eq!(written, Some(Volume(5)));
+    }
+
+    #[test]
+    fn writes_pending_changes_on_exit() {
+        let filename = "settings_test_exit.ron";
+        let (mut app, start) = app(filename, Duration::from_secs(60));
+        app.update();
+
+        app.world.resource_mut::<Volume>().0 = 7;
+        advance(&mut app, start + Duration::from_millis(100));
+        let before_exit = stored(filename);
+        app.world.send_event(AppExit);
+        advance(&mut app, start + Duration::from_millis(200));
+        let written = stored(filename);
+        delete_save(filename).unwrap();
+
+        assert_eq!(before_exit, None);
+        assert_eq!(written, Some(Volume(7)));
+    }
+
+    #[test]
+    fn writes_changes_made_during_startup() {
+        let filename = "settings_test_startup.ron";
+        let (mut app, _) = app(filename, Duration::ZERO);
+        app.add_systems(Startup, |mut volume: ResMut<Volume>| volume.0 = 9);
+        app.update();
+        let written = stored(filename);
+        delete_save(filename).unwrap();
+
+        assert_eq!(written, Some(Volume(9)));
+    }
+
+    #[test]
+    fn loads_stored_settings_without_rewriting() {
+        let filename = "settings_test_load.ron";
+        write_text(filename, "(4)").unwrap();
+        let (mut app, _) = app(filename, Duration::ZERO);
+        app.update();
+        let volume = app.world.resource::<Volume>().0;
+        let text = crate::sys::read_text(filename).unwrap();
+        delete_save(filename).unwrap();
+
+        assert_eq!(volume, 4);
+        assert_eq!(text.as_deref(), Some("(4)"));
+    }
+}
@@ -0,0 +1,172 @@
+use flate2::Crc;
+use tracing::instrument;
+
+use crate::encode::{decode, encode};
+use crate::sys::{read_save_bytes, write_save_bytes};
+use crate::SaveloadError as Error;
+
+/// Identifies a share code produced by [`export_save`].
+const MAGIC: &[u8; 4] = b"RGSV";
+
+/// Version of the share code layout.
+const VERSION: u8 = 1;
+
+/// Save format of the compressed payload, so that saves can't be imported into
+/// a build that can't read them.
+#[cfg(not(feature = "serialize-binary"))]
+const FORMAT: u8 = 0;
+#[cfg(feature = "serialize-binary")]
+const FORMAT: u8 = 1;
+
+/// Length of the header: magic, version, format and a CRC32 of the payload.
+const HEADER_LEN: usize = MAGIC.len() + 2 + 4;
+
+fn checksum(bytes: &[u8]) -> u32 {
+    let mut crc = Crc::new();
+    crc.update(bytes);
+    crc.sum()
+}
+
+/// Export the save in the given `slot` as a copy-pasteable share code.
+///
+/// The code is the base64-encoded compressed save, prefixed with a header that
+/// identifies the save format and a checksum of the save data.
+#[instrument]
+pub fn export_save(slot: &str) -> Result<String, Error> {
+    let compressed = read_save_bytes(slot)?.ok_or_else(|| Error::NoSave {
+        slot: slot.to_string(),
+    })?;
+
+    let mut bytes = Vec::with_capacity(HEADER_LEN + compressed.len());
+    bytes.extend_from_slice(MAGIC);
+    bytes.push(VERSION);
+    bytes.push(FORMAT);
+    bytes.extend_from_slice(&checksum(&compressed).to_le_bytes());
+    bytes.extend_from_slice(&compressed);
+    Ok(encode(&bytes))
+}
+
+/// Import a share code created by [`export_save`] into the given `slot`,
+/// replacing any existing save.
+///
+/// The code is validated before anything is written, so a corrupted or
+/// truncated code leaves the slot untouched.
+#[instrument(skip(code))]
+pub fn import_save(slot: &str, code: &str) -> Result<(), Error> {
+    let bytes = decode(code.trim())?;
+    if bytes.len() < HEADER_LEN || &bytes[..MAGIC.len()] != MAGIC {
+        return Err(Error::InvalidShareCode);
+    }
+
+    let (header, compressed) = bytes.split_at(HEADER_LEN);
+    let version = header[MAGIC.len()];
+    if version != VERSION {
+        return Err(Error::UnsupportedVersion { version });
+    }
+    if header[MAGIC.len() + 1] != FORMAT {
+        return Err(Error::FormatMismatch);
+    }
+    let expected = u32::from_le_bytes(header[MAGIC.len() + 2..].try_into().unwrap());
+    let found = checksum(compressed);
+    if expected != found {
+        return Err(Error::ChecksumMismatch { expected, found });
+    }
+
+    write_save_bytes(slot, compressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sys::delete_save;
+
+    /// Removes the test's save slots when dropped.
+    struct Slots(&'static [&'static str]);
+
+    impl Drop for Slots {
+        fn drop(&mut self) {
+            for slot in self.0 {
+                let _ = delete_save(slot);
+            }
+        }
+    }
+
+    fn code(magic: &[u8], version: u8, format: u8, checksum: u32, payload: &[u8]) -> String {
+        let mut bytes = magic.to_vec();
+        bytes.push(version);
+        bytes.push(format);
+        bytes.extend_from_slice(&checksum.to_le_bytes());
+        bytes.extend_from_slice(payload);
+        encode(&bytes)
+    }
+
+    #[test]
+    fn export_import_round_trip() {
+        let _slots = Slots(&["share_test_export.scn", "share_test_import.scn"]);
+        write_save_bytes("share_test_export.scn", b"save data").unwrap();
+
+        let code = export_save("share_test_export.scn").unwrap();
+        import_save("share_test_import.scn", &format!("  {code}\n")).unwrap();
+        assert_eq!(
+            read_save_bytes("share_test_import.scn").unwrap().as_deref(),
+            Some(&b"save data"[..])
+        );
+    }
+
+    #[test]
+    fn export_missing_save() {
+        assert!(matches!(
+            export_save("share_test_missing.scn"),
+            Err(Error::NoSave { slot }) if slot == "share_test_missing.scn"
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_codes() {
+        let _slots = Slots(&["share_test_rejected.scn"]);
+        let slot = "share_test_rejected.scn";
+        let payload = b"save data";
+
+        let bad_magic = code(b"NOPE", VERSION, FORMAT, checksum(payload), payload);
+        assert!(matches!(
+            import_save(slot, &bad_magic),
+            Err(Error::InvalidShareCode)
+        ));
+
+        let wrong_version = code(MAGIC, VERSION + 1, FORMAT, checksum(payload), payload);
+        assert!(matches!(
+            import_save(slot, &wrong_version),
+            Err(Error::UnsupportedVersion { version }) if version == VERSION + 1
+        ));
+
+        let unknown_format = code(MAGIC, VERSION, 0xff, checksum(payload), payload);
+        assert!(matches!(
+            import_save(slot, &unknown_format),
+            Err(Error::FormatMismatch)
+        ));
+
+        let bad_checksum = code(MAGIC, VERSION, FORMAT, checksum(payload) ^ 1, payload);
+        assert!(matches!(
+            import_save(slot, &bad_checksum),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+
+        let valid = decode(&code(MAGIC, VERSION, FORMAT, checksum(payload), payload)).unwrap();
+        let truncated_header = encode(&valid[..HEADER_LEN - 1]);
+        assert!(matches!(
+            import_save(slot, &truncated_header),
+            Err(Error::InvalidShareCode)
+        ));
+        let truncated_data = encode(&valid[..valid.len() - 1]);
+        assert!(matches!(
+            import_save(slot, &truncated_data),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+        assert!(matches!(
+            import_save(slot, "not base64!"),
+            Err(Error::Decode(_))
+        ));
+
+        assert!(read_save_bytes(slot).unwrap().is_none());
+    }
+}
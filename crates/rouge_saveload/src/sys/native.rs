@@ -1,5 +1,5 @@
 use std::{
-    fs::{self, File},
+    fs::{self, File, OpenOptions},
     io::Write,
     path::Path,
 };
@@ -19,21 +19,19 @@ pub fn save_scene(
 ) -> Result<(), Error> {
     let serialized = super::super::serialize::serialize(scene, type_registry)?;
     let compressed = super::super::compress::compress(&serialized)?;
-
-    let path = Path::new(".").join(filename);
-    let mut writer = File::create(path).map_err(Error::from)?;
-    writer.write_all(&compressed).expect("Failed to save game!");
-    Ok(())
+    write_save_bytes(filename, &compressed)
 }
 
-/// Load a bevy `DynamicScene` from the file at the given `filename`.
+/// Load a bevy `DynamicScene` from the file at the given `filename`. Returns
+/// `None` if the file does not exist.
 #[instrument(skip(type_registry))]
 pub fn load_scene(
     filename: &str,
     type_registry: &AppTypeRegistry,
 ) -> Result<Option<DynamicScene>, Error> {
-    let path = Path::new(".").join(filename);
-    let compressed = fs::read(path).map_err(Error::from)?;
+    let Some(compressed) = read_save_bytes(filename)? else {
+        return Ok(None);
+    };
     let serialized = super::super::compress::decompress(&compressed)?;
     Ok(Some(super::super::serialize::deserialize(
         &serialized,
@@ -41,17 +39,63 @@ pub fn load_scene(
     )?))
 }
 
-/// Tests for the existence of a save file.
+/// Read the raw, compressed contents of the file at the given `filename`.
+/// Returns `None` if the file does not exist.
+pub(crate) fn read_save_bytes(filename: &str) -> Result<Option<Vec<u8>>, Error> {
+    let path = Path::new(".").join(filename);
+    if !path.exists() {
+        return Ok(None);
+    }
+    fs::read(path).map(Some).map_err(Error::from)
+}
+
+/// Write raw, compressed save data to the file at the given `filename`.
+pub(crate) fn write_save_bytes(filename: &str, compressed: &[u8]) -> Result<(), Error> {
+    let path = Path::new(".").join(filename);
+    let mut writer = File::create(path).map_err(Error::from)?;
+    writer.write_all(compressed).map_err(Error::from)
+}
+
+/// Append a line of text to the file at the given `filename`, creating it if
+/// it does not exist.
+pub(crate) fn append_line(filename: &str, line: &str) -> Result<(), Error> {
+    let path = Path::new(".").join(filename);
+    let mut writer = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(Error::from)?;
+    writeln!(writer, "{line}").map_err(Error::from)
+}
+
+/// Read the text contents of the file at the given `filename`. Returns `None`
+/// if the file does not exist.
+pub(crate) fn read_text(filename: &str) -> Result<Option<String>, Error> {
+    let path = Path::new(".").join(filename);
+    if !path.exists() {
+        return Ok(None);
+    }
+    fs::read_to_string(path).map(Some).map_err(Error::from)
+}
+
+/// Replace the contents of the file at the given `filename` with `text`.
+pub(crate) fn write_text(filename: &str, text: &str) -> Result<(), Error> {
+    let path = Path::new(".").join(filename);
+    fs::write(path, text).map_err(Error::from)
+}
+
+/// Tests for the existence of a save file in the given `slot`.
 #[instrument]
-pub fn does_save_exist() -> bool {
-    Path::new("./savegame.scn").exists()
+pub fn does_save_exist(slot: &str) -> bool {
+    Path::new(".").join(slot).exists()
 }
 
-/// Deletes a save file.
+/// Deletes the save file in the given `slot`, if there is one.
 #[instrument]
-pub fn delete_save() -> Result<(), Error> {
-    if Path::new("./savegame.scn").exists() {
-        std::fs::remove_file("./savegame.scn").map_err(Error::from)?;
+pub fn delete_save(slot: &str) -> Result<(), Error> {
+    let path = Path::new(".").join(slot);
+    if path.exists() {
+        fs::remove_file(path).map_err(Error::from)?;
     }
     Ok(())
 }
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use bevy::ecs::reflect::AppTypeRegistry;
 use bevy::scene::DynamicScene;
 use lazy_static::lazy_static;
@@ -5,7 +7,8 @@ use parking_lot::Mutex;
 use tracing::{error, instrument, warn};
 
 lazy_static! {
-    static ref SAVE_GAME: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+    static ref SAVE_GAME: Mutex<HashMap<String, Vec<u8>>> = Mutex::new(HashMap::new());
+    static ref TEXT: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
 }
 
 use crate::SaveloadError as Error;
@@ -19,33 +22,17 @@ pub fn save_scene(
 ) -> Result<(), Error> {
     let serialized = super::super::serialize::serialize(scene, type_registry)?;
     let compressed = super::super::compress::compress(&serialized)?;
-    let encoded = super::super::encode::encode(&compressed);
-
-    if let Err(e) = web_sys::window().unwrap().local_storage() {
-        warn!("Local storage unavailable: {:?}", e);
-        *SAVE_GAME.lock() = compressed;
-    } else if let Some(local_storage) = web_sys::window().unwrap().local_storage().unwrap() {
-        let result = local_storage.set(filename, &encoded);
-        if let Err(e) = result {
-            error!("Failed to save game ({} bytes): {:?}", encoded.len(), e);
-        }
-    }
-    Ok(())
+    write_save_bytes(filename, &compressed)
 }
 
-/// Load a bevy `DynamicScene` from the file at the given `filename`.
+/// Load a bevy `DynamicScene` from the file at the given `filename`. Returns
+/// `None` if there is no save.
 #[instrument(skip(type_registry))]
 pub fn load_scene(
     filename: &str,
     type_registry: &AppTypeRegistry,
 ) -> Result<Option<DynamicScene>, Error> {
-    let compressed = if let Err(e) = web_sys::window().unwrap().local_storage() {
-        warn!("Local storage unavailable: {:?}", e);
-        std::mem::take(&mut *SAVE_GAME.lock())
-    } else if let Some(local_storage) = web_sys::window().unwrap().local_storage().unwrap() {
-        let encoded = local_storage.get(filename).unwrap().unwrap();
-        super::super::encode::decode(&encoded)?
-    } else {
+    let Some(compressed) = read_save_bytes(filename)? else {
         return Ok(None);
     };
     let serialized = super::super::compress::decompress(&compressed)?;
@@ -56,24 +43,118 @@ pub fn load_scene(
     )?))
 }
 
-/// Tests for the existence of a save file.
+/// Read the raw, compressed save data stored under the given `filename`.
+/// Returns `None` if there is no save.
+pub(crate) fn read_save_bytes(filename: &str) -> Result<Option<Vec<u8>>, Error> {
+    if let Err(e) = web_sys::window().unwrap().local_storage() {
+        warn!("Local storage unavailable: {:?}", e);
+        return Ok(SAVE_GAME.lock().get(filename).cloned());
+    }
+    if let Some(local_storage) = web_sys::window().unwrap().local_storage().unwrap() {
+        if let Some(encoded) = local_storage
+            .get_item(filename)
+            .map_err(|message| Error::JS { message })?
+        {
+            return Ok(Some(super::super::encode::decode(&encoded)?));
+        }
+    }
+    Ok(None)
+}
+
+/// Write raw, compressed save data under the given `filename`.
+pub(crate) fn write_save_bytes(filename: &str, compressed: &[u8]) -> Result<(), Error> {
+    let encoded = super::super::encode::encode(compressed);
+
+    if let Err(e) = web_sys::window().unwrap().local_storage() {
+        warn!("Local storage unavailable: {:?}", e);
+        SAVE_GAME
+            .lock()
+            .insert(filename.to_string(), compressed.to_vec());
+    } else if let Some(local_storage) = web_sys::window().unwrap().local_storage().unwrap() {
+        if let Err(message) = local_storage.set(filename, &encoded) {
+            error!(
+                "Failed to save game ({} bytes): {:?}",
+                encoded.len(),
+                message
+            );
+            return Err(Error::JS { message });
+        }
+    }
+    Ok(())
+}
+
+/// Append a line of text to the text stored under the given `filename`.
+pub(crate) fn append_line(filename: &str, line: &str) -> Result<(), Error> {
+    if let Err(e) = web_sys::window().unwrap().local_storage() {
+        warn!("Local storage unavailable: {:?}", e);
+        let mut text = TEXT.lock();
+        let text = text.entry(filename.to_string()).or_default();
+        text.push_str(line);
+        text.push('\n');
+        return Ok(());
+    }
+    if let Some(local_storage) = web_sys::window().unwrap().local_storage().unwrap() {
+        let mut text = local_storage
+            .get_item(filename)
+            .map_err(|message| Error::JS { message })?
+            .unwrap_or_default();
+        text.push_str(line);
+        text.push('\n');
+        local_storage
+            .set_item(filename, &text)
+            .map_err(|message| Error::JS { message })?;
+    }
+    Ok(())
+}
+
+/// Read the text stored under the given `filename`. Returns `None` if there is
+/// nothing stored.
+pub(crate) fn read_text(filename: &str) -> Result<Option<String>, Error> {
+    if let Err(e) = web_sys::window().unwrap().local_storage() {
+        warn!("Local storage unavailable: {:?}", e);
+        return Ok(TEXT.lock().get(filename).cloned());
+    }
+    if let Some(local_storage) = web_sys::window().unwrap().local_storage().unwrap() {
+        return local_storage
+            .get_item(filename)
+            .map_err(|message| Error::JS { message });
+    }
+    Ok(None)
+}
+
+/// Replace the text stored under the given `filename` with `text`.
+pub(crate) fn write_text(filename: &str, text: &str) -> Result<(), Error> {
+    if let Err(e) = web_sys::window().unwrap().local_storage() {
+        warn!("Local storage unavailable: {:?}", e);
+        TEXT.lock().insert(filename.to_string(), text.to_string());
+        return Ok(());
+    }
+    if let Some(local_storage) = web_sys::window().unwrap().local_storage().unwrap() {
+        local_storage
+            .set_item(filename, text)
+            .map_err(|message| Error::JS { message })?;
+    }
+    Ok(())
+}
+
+/// Tests for the existence of a save in the given `slot`.
 #[instrument]
-pub fn does_save_exist() -> bool {
+pub fn does_save_exist(slot: &str) -> bool {
     if let Err(_e) = web_sys::window().unwrap().local_storage() {
-        return !SAVE_GAME.lock().is_empty();
+        return SAVE_GAME.lock().contains_key(slot);
     }
     if let Some(local_storage) = web_sys::window().unwrap().local_storage().unwrap() {
-        return local_storage.get_item("savegame.scn").unwrap().is_some();
+        return local_storage.get_item(slot).unwrap().is_some();
     }
     false
 }
 
-/// Deletes a save file.
+/// Deletes the save in the given `slot`, if there is one.
 #[instrument]
-pub fn delete_save() -> Result<(), Error> {
+pub fn delete_save(slot: &str) -> Result<(), Error> {
     if let Err(e) = web_sys::window().unwrap().local_storage() {
         warn!("Local storage unavailable: {:?}", e);
-        std::mem::take(&mut *SAVE_GAME.lock());
+        SAVE_GAME.lock().remove(slot);
         return Ok(());
     }
     if let Some(local_storage) = web_sys::window()
@@ -81,7 +162,9 @@ pub fn delete_save() -> Result<(), Error> {
         .local_storage()
         .map_err(|message| Error::JS { message })?
     {
-        local_storage.remove_item("savegame.scn").unwrap();
+        local_storage
+            .remove_item(slot)
+            .map_err(|message| Error::JS { message })?;
     }
     Ok(())
 }
@@ -0,0 +1,437 @@
+//! Helpers for verifying that a `World` survives a save/load round trip.
+//!
+//! Call [`assert_round_trip`] from a test after setting up a representative
+//! `World` to catch components that were never registered for reflection, or
+//! whose values change when saved and loaded again.
+
+use std::any::TypeId;
+use std::collections::HashSet;
+use std::fmt;
+
+use bevy::ecs::entity::EntityHashMap;
+use bevy::prelude::*;
+use bevy::scene::DynamicScene;
+
+use crate::serialize;
+use crate::SaveloadError as Error;
+
+type Serializer = fn(&DynamicScene, &AppTypeRegistry) -> Result<Vec<u8>, Error>;
+type Deserializer = fn(&[u8], &AppTypeRegistry) -> Result<DynamicScene, Error>;
+
+/// Every save format enabled in this build.
+const FORMATS: &[(&str, Serializer, Deserializer)] = &[
+    ("ron", serialize::serialize_ron, serialize::deserialize_ron),
+    #[cfg(feature = "serialize-binary")]
+    (
+        "binary",
+        serialize::serialize_binary,
+        serialize::deserialize_binary,
+    ),
+];
+
+/// Saves a `World` through every enabled format, loads it into a fresh `World`
+/// and compares the result against the original.
+///
+/// Use [`check_round_trip`] or [`assert_round_trip`] for the default settings.
+#[derive(Default)]
+pub struct RoundTrip {
+    ignored: HashSet<TypeId>,
+}
+
+impl RoundTrip {
+    /// Create a new round trip check.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Skip components of type `T` when checking entities. Useful for
+    /// components that are intentionally left out of saves.
+    pub fn ignore<T: Component>(mut self) -> Self {
+        self.ignored.insert(TypeId::of::<T>());
+        self
+    }
+
+    /// Round trip the `World`, returning a report of every problem found.
+    ///
+    /// The `World` must contain an [`AppTypeRegistry`] resource.
+    pub fn check(&self, world: &World) -> Result<(), RoundTripReport> {
+        let type_registry = world.resource::<AppTypeRegistry>();
+        let mut report = RoundTripReport::default();
+
+        self.check_registered(world, type_registry, &mut report);
+
+        let scene = DynamicScene::from_world(world);
+        for (format, serialize, deserialize) in FORMATS {
+            match round_trip(&scene, type_registry, *serialize, *deserialize) {
+                Ok(loaded) => {
+                    self.compare(format, world, &loaded, type_registry, &mut report);
+                }
+                Err(error) => report.problems.push(Problem::Failed { format, error }),
+            }
+        }
+
+        if report.problems.is_empty() {
+            Ok(())
+        } else {
+            Err(report)
+        }
+    }
+
+    /// Round trip the `World`, panicking with a readable report if any
+    /// problems are found.
+    pub fn assert(&self, world: &World) {
+        if let Err(report) = self.check(world) {
+            panic!("{report}");
+        }
+    }
+
+    fn check_registered(
+        &self,
+        world: &World,
+        type_registry: &AppTypeRegistry,
+        report: &mut RoundTripReport,
+    ) {
+        let type_registry = type_registry.read();
+        for entity in world.iter_entities() {
+            for info in world.inspect_entity(entity.id()) {
+                let Some(type_id) = info.type_id() else {
+                    continue;
+                };
+                if self.ignored.contains(&type_id) {
+                    continue;
+                }
+                let registered = type_registry
+                    .get(type_id)
+                    .is_some_and(|registration| registration.data::<ReflectComponent>().is_some());
+                if !registered {
+                    report.problems.push(Problem::Unregistered {
+                        entity: entity.id(),
+                        component: info.name().to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    fn compare(
+        &self,
+        format: &'static str,
+        original: &World,
+        loaded: &World,
+        type_registry: &AppTypeRegistry,
+        report: &mut RoundTripReport,
+    ) {
+        let type_registry = type_registry.read();
+        for entity in original.iter_entities() {
+            let Some(loaded_entity) = loaded.get_entity(entity.id()) else {
+                report.problems.push(Problem::MissingEntity {
+                    format,
+                    entity: entity.id(),
+                });
+                continue;
+            };
+            for registration in type_registry.iter() {
+                if self.ignored.contains(&registration.type_id()) {
+                    continue;
+                }
+                let Some(reflect_component) = registration.data::<ReflectComponent>() else {
+                    continue;
+                };
+                let component = registration.type_info().type_path().to_string();
+                match (
+                    reflect_component.reflect(entity),
+                    reflect_component.reflect(loaded_entity),
+                ) {
+                    (Some(expected), Some(found)) => {
+                        if !reflect_eq(expected, found) {
+                            report.problems.push(Problem::Mismatch {
+                                format,
+                                entity: entity.id(),
+                                component,
+                                expected: format!("{expected:?}"),
+                                found: format!("{found:?}"),
+                            });
+                        }
+                    }
+                    (Some(_), None) => report.problems.push(Problem::MissingComponent {
+                        format,
+                        entity: entity.id(),
+                        component,
+                    }),
+                    (None, Some(_)) => report.problems.push(Problem::ExtraComponent {
+                        format,
+                        entity: entity.id(),
+                        component,
+                    }),
+                    (None, None) => {}
+                }
+            }
+        }
+        for entity in loaded.iter_entities() {
+            if original.get_entity(entity.id()).is_none() {
+                report.problems.push(Problem::ExtraEntity {
+                    format,
+                    entity: entity.id(),
+                });
+            }
+        }
+    }
+}
+
+/// Round trip the `World` with the default settings, returning a report of
+/// every problem found.
+pub fn check_round_trip(world: &World) -> Result<(), RoundTripReport> {
+    RoundTrip::new().check(world)
+}
+
+/// Round trip the `World` with the default settings, panicking with a readable
+/// report if any problems are found.
+pub fn assert_round_trip(world: &World) {
+    RoundTrip::new().assert(world)
+}
+
+/// Serialize and deserialize the scene, then write it to a fresh `World` using
+/// the same entity ids as the original.
+fn round_trip(
+    scene: &DynamicScene,
+    type_registry: &AppTypeRegistry,
+    serialize: Serializer,
+    deserialize: Deserializer,
+) -> Result<World, Error> {
+    let bytes = serialize(scene, type_registry)?;
+    let scene = deserialize(&bytes, type_registry)?;
+
+    let mut world = World::new();
+    world.insert_resource(type_registry.clone());
+
+    // Spawning each entity at its original id up front keeps entity references
+    // in components comparable with the original `World`.
+    let mut entity_map = EntityHashMap::default();
+    for entity in &scene.entities {
+        world.get_or_spawn(entity.entity);
+        entity_map.insert(entity.entity, entity.entity);
+    }
+
+    scene.write_to_world(&mut world, &mut entity_map)?;
+    Ok(world)
+}
+
+/// Compare two reflected values, falling back to their debug representation
+/// for types that don't support reflected equality.
+fn reflect_eq(a: &dyn Reflect, b: &dyn Reflect) -> bool {
+    a.reflect_partial_eq(b)
+        .unwrap_or_else(|| format!("{a:?}") == format!("{b:?}"))
+}
+
+/// A single problem found during a round trip.
+#[derive(Debug)]
+pub enum Problem {
+    /// A component is not registered with `ReflectComponent`, so it is never
+    /// saved.
+    Unregistered { entity: Entity, component: String },
+    /// The `World` failed to save or load in the given format.
+    Failed { format: &'static str, error: Error },
+    /// An entity is missing after loading.
+    MissingEntity {
+        format: &'static str,
+        entity: Entity,
+    },
+    /// An entity was added by loading.
+    ExtraEntity {
+        format: &'static str,
+        entity: Entity,
+    },
+    /// A component is missing from an entity after loading.
+    MissingComponent {
+        format: &'static str,
+        entity: Entity,
+        component: String,
+    },
+    /// A component was added to an entity after loading.
+    ExtraComponent {
+        format: &'static str,
+        entity: Entity,
+        component: String,
+    },
+    /// A component has a different value after loading.
+    Mismatch {
+        format: &'static str,
+        entity: Entity,
+        component: String,
+        expected: String,
+        found: String,
+    },
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::Unregistered { entity, component } => write!(
+                f,
+                "{entity:?}: component `{component}` is not registered for reflection"
+            ),
+            Problem::Failed { format, error } => {
+                write!(f, "[{format}] {error}")?;
+                let mut source = std::error::Error::source(error);
+                while let Some(error) = source {
+                    write!(f, ": {error}")?;
+                    source = error.source();
+                }
+                Ok(())
+            }
+            Problem::MissingEntity { format, entity } => {
+                write!(f, "[{format}] {entity:?}: entity missing after load")
+            }
+            Problem::ExtraEntity { format, entity } => {
+                write!(f, "[{format}] {entity:?}: entity added by load")
+            }
+            Problem::MissingComponent {
+                format,
+                entity,
+                component,
+            } => write!(
+                f,
+                "[{format}] {entity:?}: component `{component}` missing after load"
+            ),
+            Problem::ExtraComponent {
+                format,
+                entity,
+                component,
+            } => write!(
+                f,
+                "[{format}] {entity:?}: component `{component}` added by load"
+            ),
+            Problem::Mismatch {
+                format,
+                entity,
+                component,
+                expected,
+                found,
+            } => write!(
+                f,
+                "[{format}] {entity:?}: component `{component}` differs\n    expected: {expected}\n    found:    {found}"
+            ),
+        }
+    }
+}
+
+/// Every problem found during a round trip.
+#[derive(Debug, Default)]
+pub struct RoundTripReport {
+    pub problems: Vec<Problem>,
+}
+
+impl fmt::Display for RoundTripReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "World failed to round trip with {} problem(s):",
+            self.problems.len()
+        )?;
+        for problem in &self.problems {
+            writeln!(f, "  {problem}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for RoundTripReport {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Component, Reflect, Default, Debug, PartialEq)]
+    #[reflect(Component)]
+    struct Health(u32);
+
+    #[derive(Component, Reflect, Default, Debug, PartialEq)]
+    #[reflect(Component)]
+    struct Lossy {
+        kept: u32,
+        #[reflect(skip_serializing)]
+        lost: u32,
+    }
+
+    #[derive(Component)]
+    struct Unregistered;
+
+    fn world() -> World {
+        let mut world = World::new();
+        let type_registry = AppTypeRegistry::default();
+        {
+            let mut type_registry = type_registry.write();
+            type_registry.register::<Health>();
+            type_registry.register::<Lossy>();
+        }
+        world.insert_resource(type_registry);
+        world
+    }
+
+    #[test]
+    fn clean_world_passes() {
+        let mut world = world();
+        world.spawn(Health(3));
+        world.spawn((Health(5), Lossy { kept: 1, lost: 0 }));
+        assert_round_trip(&world);
+    }
+
+    #[test]
+    fn reports_unregistered_components() {
+        let mut world = world();
+        let entity = world.spawn((Health(3), Unregistered)).id();
+
+        let report = check_round_trip(&world).unwrap_err();
+        assert!(matches!(
+            report.problems.as_slice(),
+            [Problem::Unregistered { entity: e, component }]
+                if *e == entity && component.ends_with("Unregistered")
+        ));
+        assert!(report
+            .to_string()
+            .contains("is not registered for reflection"));
+    }
+
+    #[test]
+    fn reports_changed_values() {
+        let mut world = world();
+        world.spawn(Lossy { kept: 1, lost: 2 });
+
+        let report = check_round_trip(&world).unwrap_err();
+        assert_eq!(report.problems.len(), FORMATS.len());
+        assert!(report
+            .problems
+            .iter()
+            .all(|problem| matches!(problem, Problem::Mismatch { .. })));
+        let message = report.to_string();
+        assert!(message.contains("differs"));
+        assert!(message.contains("lost: 2"));
+        assert!(message.contains("lost: 0"));
+    }
+
+    #[test]
+    fn ignored_components_are_skipped() {
+        let mut world = world();
+        world.spawn((Lossy { kept: 1, lost: 2 }, Unregistered));
+
+        RoundTrip::new()
+            .ignore::<Lossy>()
+            .ignore::<Unregistered>()
+            .assert(&world);
+    }
+
+    #[test]
+    fn reports_extra_entities() {
+        let original = world();
+        let mut loaded = world();
+        let entity = loaded.spawn(Health(1)).id();
+
+        let mut report = RoundTripReport::default();
+        let type_registry = original.resource::<AppTypeRegistry>();
+        RoundTrip::new().compare("ron", &original, &loaded, type_registry, &mut report);
+        assert!(matches!(
+            report.problems.as_slice(),
+            [Problem::ExtraEntity { entity: e, .. }] if *e == entity
+        ));
+    }
+}